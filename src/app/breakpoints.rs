@@ -0,0 +1,109 @@
+use std::fmt::{Display, Formatter, Result};
+
+use rcmmm::{cellkind, Event, Grid};
+
+/// Something to pause the simulation for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Condition {
+    /// A cell moves or is generated into the square `(x, y)`, even if another
+    /// cell was there before.
+    Occupied(u32, u32),
+    /// There are more than this many cells of the kind with this id.
+    MoreThan(u8, usize),
+    /// There are fewer than this many cells of the kind with this id.
+    FewerThan(u8, usize),
+    /// A generator had something to copy but the square in front stayed full.
+    GeneratorBlocked,
+    /// This tick has been reached.
+    Tick(u64),
+}
+
+impl Condition {
+    fn is_met(&self, grid: &Grid, tick: u64) -> bool {
+        let count = |id: u8| grid.cells.iter().flatten().filter(|cell| cell.id == id).count();
+        match *self {
+            Condition::Occupied(x, y) => grid.cell(x, y).is_some_and(|cell| !cell.is_empty()),
+            Condition::MoreThan(id, limit) => count(id) > limit,
+            Condition::FewerThan(id, limit) => count(id) < limit,
+            Condition::GeneratorBlocked => {
+                grid.events.iter().any(|event| matches!(event, Event::GenerationBlocked { .. }))
+            }
+            Condition::Tick(at) => tick >= at,
+        }
+    }
+
+    // Triggers even if the condition was already met, e.g. for a push chain
+    // passing through a square that never empties
+    fn happened(&self, grid: &Grid) -> bool {
+        let Condition::Occupied(x, y) = *self else {
+            return false;
+        };
+        let square = (x as usize, y as usize);
+        grid.events.iter().any(|event| match *event {
+            Event::Moved { to, .. } => to == square,
+            Event::Generated { at, .. } => at == square,
+            _ => false,
+        })
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let name = |id: u8| cellkind::get(id).map_or(format!("cell {}", id), |kind| kind.name().to_string());
+        match self {
+            Condition::Occupied(x, y) => write!(f, "A cell entered square {},{}", x, y),
+            Condition::MoreThan(id, limit) => write!(f, "More than {} {} cells", limit, name(*id)),
+            Condition::FewerThan(id, limit) => write!(f, "Fewer than {} {} cells", limit, name(*id)),
+            Condition::GeneratorBlocked => write!(f, "A generator is blocked"),
+            Condition::Tick(tick) => write!(f, "Tick {} reached", tick),
+        }
+    }
+}
+
+/// Conditions checked after every tick. Each one only triggers when it
+/// becomes true, so playing on doesn't stop again right away. Watched squares
+/// also trigger whenever a cell moves into them.
+#[derive(Default)]
+pub struct Breakpoints {
+    // Condition and whether it was met after the last check
+    list: Vec<(Condition, bool)>,
+}
+
+impl Breakpoints {
+    pub fn new() -> Breakpoints {
+        Breakpoints::default()
+    }
+
+    pub fn conditions(&self) -> Vec<Condition> {
+        self.list.iter().map(|(condition, _)| *condition).collect()
+    }
+
+    /// Replaces the conditions. They count as met if they already are, so
+    /// only later changes trigger them.
+    pub fn set(&mut self, conditions: Vec<Condition>, grid: &Grid, tick: u64) {
+        self.list = conditions.into_iter().map(|condition| (condition, false)).collect();
+        self.rearm(grid, tick);
+    }
+
+    /// Takes the state of `grid` as the starting point without triggering
+    /// anything, e.g. after the grid was edited or the timeline moved.
+    pub fn rearm(&mut self, grid: &Grid, tick: u64) {
+        for (condition, met) in &mut self.list {
+            *met = condition.is_met(grid, tick);
+        }
+    }
+
+    /// Checks the grid after tick `tick`. Returns the first condition that
+    /// became true.
+    pub fn check(&mut self, grid: &Grid, tick: u64) -> Option<Condition> {
+        let mut triggered = None;
+        for (condition, met) in &mut self.list {
+            let now = condition.is_met(grid, tick);
+            if (now && !*met || condition.happened(grid)) && triggered.is_none() {
+                triggered = Some(*condition);
+            }
+            *met = now;
+        }
+        triggered
+    }
+}
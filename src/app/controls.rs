@@ -0,0 +1,455 @@
+use macroquad::prelude::*;
+
+/// Everything the keyboard can do. Screens ask `Bindings` whether an action
+/// was triggered instead of checking key codes themselves.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    RotateClockwise,
+    RotateCounterClockwise,
+    PreviousCell,
+    NextCell,
+    TogglePause,
+    PauseMenu,
+    Resize,
+    Undo,
+    Redo,
+    ToggleEventLog,
+    StepBack,
+    StepForward,
+    Breakpoints,
+    ToggleInspector,
+    ToggleStats,
+    ExportStats,
+    ToggleHeatmap,
+    ToggleTrails,
+    HotbarSlot1,
+    HotbarSlot2,
+    HotbarSlot3,
+    HotbarSlot4,
+    HotbarSlot5,
+    HotbarSlot6,
+    HotbarSlot7,
+    HotbarSlot8,
+    HotbarSlot9,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    MenuConfirm,
+    MenuBack,
+}
+
+/// Actions only conflict with actions used on the same kind of screen, so the
+/// game and the menus can share keys like Escape.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ActionGroup {
+    Game,
+    Hotbar,
+    Menu,
+}
+
+impl Action {
+    pub const ALL: [Action; 37] = [
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::RotateClockwise,
+        Action::RotateCounterClockwise,
+        Action::PreviousCell,
+        Action::NextCell,
+        Action::TogglePause,
+        Action::PauseMenu,
+        Action::Resize,
+        Action::Undo,
+        Action::Redo,
+        Action::ToggleEventLog,
+        Action::StepBack,
+        Action::StepForward,
+        Action::Breakpoints,
+        Action::ToggleInspector,
+        Action::ToggleStats,
+        Action::ExportStats,
+        Action::ToggleHeatmap,
+        Action::ToggleTrails,
+        Action::HotbarSlot1,
+        Action::HotbarSlot2,
+        Action::HotbarSlot3,
+        Action::HotbarSlot4,
+        Action::HotbarSlot5,
+        Action::HotbarSlot6,
+        Action::HotbarSlot7,
+        Action::HotbarSlot8,
+        Action::HotbarSlot9,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::MenuConfirm,
+        Action::MenuBack,
+    ];
+
+    pub const HOTBAR_SLOTS: [Action; 9] = [
+        Action::HotbarSlot1,
+        Action::HotbarSlot2,
+        Action::HotbarSlot3,
+        Action::HotbarSlot4,
+        Action::HotbarSlot5,
+        Action::HotbarSlot6,
+        Action::HotbarSlot7,
+        Action::HotbarSlot8,
+        Action::HotbarSlot9,
+    ];
+
+    /// Key used for the action in the settings file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Action::PanUp => "pan_up",
+            Action::PanDown => "pan_down",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::RotateClockwise => "rotate_clockwise",
+            Action::RotateCounterClockwise => "rotate_counter_clockwise",
+            Action::PreviousCell => "previous_cell",
+            Action::NextCell => "next_cell",
+            Action::TogglePause => "toggle_pause",
+            Action::PauseMenu => "pause_menu",
+            Action::Resize => "resize",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ToggleEventLog => "toggle_event_log",
+            Action::StepBack => "step_back",
+            Action::StepForward => "step_forward",
+            Action::Breakpoints => "breakpoints",
+            Action::ToggleInspector => "toggle_inspector",
+            Action::ToggleStats => "toggle_stats",
+            Action::ExportStats => "export_stats",
+            Action::ToggleHeatmap => "toggle_heatmap",
+            Action::ToggleTrails => "toggle_trails",
+            Action::HotbarSlot1 => "hotbar_slot_1",
+            Action::HotbarSlot2 => "hotbar_slot_2",
+            Action::HotbarSlot3 => "hotbar_slot_3",
+            Action::HotbarSlot4 => "hotbar_slot_4",
+            Action::HotbarSlot5 => "hotbar_slot_5",
+            Action::HotbarSlot6 => "hotbar_slot_6",
+            Action::HotbarSlot7 => "hotbar_slot_7",
+            Action::HotbarSlot8 => "hotbar_slot_8",
+            Action::HotbarSlot9 => "hotbar_slot_9",
+            Action::MenuUp => "menu_up",
+            Action::MenuDown => "menu_down",
+            Action::MenuLeft => "menu_left",
+            Action::MenuRight => "menu_right",
+            Action::MenuConfirm => "menu_confirm",
+            Action::MenuBack => "menu_back",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.iter().find(|action| action.id() == id).copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::PanUp => "Pan up",
+            Action::PanDown => "Pan down",
+            Action::PanLeft => "Pan left",
+            Action::PanRight => "Pan right",
+            Action::RotateClockwise => "Rotate clockwise",
+            Action::RotateCounterClockwise => "Rotate counter-clockwise",
+            Action::PreviousCell => "Previous cell",
+            Action::NextCell => "Next cell",
+            Action::TogglePause => "Play / pause",
+            Action::PauseMenu => "Pause menu",
+            Action::Resize => "Resize grid",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ToggleEventLog => "Event log",
+            Action::StepBack => "Tick back",
+            Action::StepForward => "Tick forward",
+            Action::Breakpoints => "Breakpoints",
+            Action::ToggleInspector => "Inspector",
+            Action::ToggleStats => "Statistics",
+            Action::ExportStats => "Export statistics",
+            Action::ToggleHeatmap => "Heatmap",
+            Action::ToggleTrails => "Movement trails",
+            Action::HotbarSlot1 => "Slot 1",
+            Action::HotbarSlot2 => "Slot 2",
+            Action::HotbarSlot3 => "Slot 3",
+            Action::HotbarSlot4 => "Slot 4",
+            Action::HotbarSlot5 => "Slot 5",
+            Action::HotbarSlot6 => "Slot 6",
+            Action::HotbarSlot7 => "Slot 7",
+            Action::HotbarSlot8 => "Slot 8",
+            Action::HotbarSlot9 => "Slot 9",
+            Action::MenuUp => "Up",
+            Action::MenuDown => "Down",
+            Action::MenuLeft => "Left",
+            Action::MenuRight => "Right",
+            Action::MenuConfirm => "Confirm",
+            Action::MenuBack => "Back",
+        }
+    }
+
+    pub fn group(&self) -> ActionGroup {
+        match self {
+            Action::HotbarSlot1
+            | Action::HotbarSlot2
+            | Action::HotbarSlot3
+            | Action::HotbarSlot4
+            | Action::HotbarSlot5
+            | Action::HotbarSlot6
+            | Action::HotbarSlot7
+            | Action::HotbarSlot8
+            | Action::HotbarSlot9 => ActionGroup::Hotbar,
+            Action::MenuUp
+            | Action::MenuDown
+            | Action::MenuLeft
+            | Action::MenuRight
+            | Action::MenuConfirm
+            | Action::MenuBack => ActionGroup::Menu,
+            _ => ActionGroup::Game,
+        }
+    }
+
+    fn conflicts_with(&self, other: &Action) -> bool {
+        // Hotbar keys work inside the game, so they can't share keys with it
+        let in_game = |group| group == ActionGroup::Game || group == ActionGroup::Hotbar;
+        self != other && (self.group() == other.group() || in_game(self.group()) && in_game(other.group()))
+    }
+
+    // Second key that always triggers the action, next to the rebindable one
+    fn alternate_binding(&self) -> Option<KeyBinding> {
+        match self {
+            Action::Redo => Some(KeyBinding::ctrl_shift(KeyCode::Z)),
+            _ => None,
+        }
+    }
+
+    fn default_binding(&self) -> KeyBinding {
+        match self {
+            Action::PanUp => KeyBinding::new(KeyCode::W),
+            Action::PanDown => KeyBinding::new(KeyCode::S),
+            Action::PanLeft => KeyBinding::new(KeyCode::A),
+            Action::PanRight => KeyBinding::new(KeyCode::D),
+            Action::RotateClockwise => KeyBinding::new(KeyCode::E),
+            Action::RotateCounterClockwise => KeyBinding::new(KeyCode::Q),
+            Action::PreviousCell => KeyBinding::new(KeyCode::Z),
+            Action::NextCell => KeyBinding::new(KeyCode::X),
+            Action::TogglePause => KeyBinding::new(KeyCode::Space),
+            Action::PauseMenu => KeyBinding::new(KeyCode::Escape),
+            Action::Resize => KeyBinding::new(KeyCode::R),
+            Action::Undo => KeyBinding::ctrl(KeyCode::Z),
+            Action::Redo => KeyBinding::ctrl(KeyCode::Y),
+            Action::ToggleEventLog => KeyBinding::new(KeyCode::L),
+            Action::StepBack => KeyBinding::new(KeyCode::Comma),
+            Action::StepForward => KeyBinding::new(KeyCode::Period),
+            Action::Breakpoints => KeyBinding::new(KeyCode::B),
+            Action::ToggleInspector => KeyBinding::new(KeyCode::I),
+            Action::ToggleStats => KeyBinding::new(KeyCode::T),
+            Action::ExportStats => KeyBinding::ctrl(KeyCode::E),
+            Action::ToggleHeatmap => KeyBinding::new(KeyCode::H),
+            Action::ToggleTrails => KeyBinding::new(KeyCode::M),
+            Action::HotbarSlot1 => KeyBinding::new(KeyCode::Key1),
+            Action::HotbarSlot2 => KeyBinding::new(KeyCode::Key2),
+            Action::HotbarSlot3 => KeyBinding::new(KeyCode::Key3),
+            Action::HotbarSlot4 => KeyBinding::new(KeyCode::Key4),
+            Action::HotbarSlot5 => KeyBinding::new(KeyCode::Key5),
+            Action::HotbarSlot6 => KeyBinding::new(KeyCode::Key6),
+            Action::HotbarSlot7 => KeyBinding::new(KeyCode::Key7),
+            Action::HotbarSlot8 => KeyBinding::new(KeyCode::Key8),
+            Action::HotbarSlot9 => KeyBinding::new(KeyCode::Key9),
+            Action::MenuUp => KeyBinding::new(KeyCode::Up),
+            Action::MenuDown => KeyBinding::new(KeyCode::Down),
+            Action::MenuLeft => KeyBinding::new(KeyCode::Left),
+            Action::MenuRight => KeyBinding::new(KeyCode::Right),
+            Action::MenuConfirm => KeyBinding::new(KeyCode::Enter),
+            Action::MenuBack => KeyBinding::new(KeyCode::Escape),
+        }
+    }
+}
+
+// Keys that can be bound, also used to turn key names back into key codes
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::Insert, KeyCode::Delete, KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::Minus, KeyCode::Equal, KeyCode::LeftBracket, KeyCode::RightBracket, KeyCode::Backslash,
+    KeyCode::Semicolon, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::GraveAccent,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4, KeyCode::Kp5,
+    KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+];
+
+fn ctrl_down() -> bool {
+    is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+}
+
+fn shift_down() -> bool {
+    is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift)
+}
+
+/// A key, optionally with Ctrl and/or Shift held.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub ctrl: bool,
+    pub shift: bool,
+}
+
+impl KeyBinding {
+    pub fn new(key: KeyCode) -> KeyBinding {
+        KeyBinding { key, ctrl: false, shift: false }
+    }
+
+    pub fn ctrl(key: KeyCode) -> KeyBinding {
+        KeyBinding { key, ctrl: true, shift: false }
+    }
+
+    pub fn ctrl_shift(key: KeyCode) -> KeyBinding {
+        KeyBinding { key, ctrl: true, shift: true }
+    }
+
+    /// Binding for a key that was just pressed, with whatever modifiers are
+    /// held. Returns None for keys that can't be bound, like Ctrl on its own.
+    pub fn capture(key: KeyCode) -> Option<KeyBinding> {
+        if BINDABLE_KEYS.contains(&key) {
+            Some(KeyBinding { key, ctrl: ctrl_down(), shift: shift_down() })
+        } else {
+            None
+        }
+    }
+
+    /// Parses names like `W`, `Space`, `Ctrl+Z` or `Ctrl+Shift+Z`, as written
+    /// by `to_string`.
+    pub fn parse(text: &str) -> Option<KeyBinding> {
+        let name = text.trim();
+        let (ctrl, name) = match name.strip_prefix("Ctrl+") {
+            Some(name) => (true, name),
+            None => (false, name),
+        };
+        let (shift, name) = match name.strip_prefix("Shift+") {
+            Some(name) => (true, name),
+            None => (false, name),
+        };
+        let key = BINDABLE_KEYS.iter().find(|key| format!("{:?}", key) == name)?;
+        Some(KeyBinding { key: *key, ctrl, shift })
+    }
+
+    // `Ctrl+Shift+` and the like, in front of the key name
+    fn modifier_prefix(&self) -> &'static str {
+        match (self.ctrl, self.shift) {
+            (true, true) => "Ctrl+Shift+",
+            (true, false) => "Ctrl+",
+            (false, true) => "Shift+",
+            (false, false) => "",
+        }
+    }
+
+    /// Whether pressing the binding also types a character into text fields.
+    pub fn types_text(&self) -> bool {
+        let key = format!("{:?}", self.key);
+        !self.ctrl && (key.len() == 1 || key.starts_with("Key") || key.starts_with("Kp") || matches!(
+            self.key,
+            KeyCode::Space
+                | KeyCode::Minus
+                | KeyCode::Equal
+                | KeyCode::LeftBracket
+                | KeyCode::RightBracket
+                | KeyCode::Backslash
+                | KeyCode::Semicolon
+                | KeyCode::Apostrophe
+                | KeyCode::Comma
+                | KeyCode::Period
+                | KeyCode::Slash
+                | KeyCode::GraveAccent
+        ))
+    }
+
+    // Modifiers have to match exactly, so Z, Shift+Z, Ctrl+Z and Ctrl+Shift+Z
+    // can all do different things
+    fn modifiers_match(&self) -> bool {
+        self.ctrl == ctrl_down() && self.shift == shift_down()
+    }
+
+    pub fn pressed(&self) -> bool {
+        is_key_pressed(self.key) && self.modifiers_match()
+    }
+
+    pub fn down(&self) -> bool {
+        is_key_down(self.key) && self.modifiers_match()
+    }
+
+    /// Short name for showing in the UI, e.g. `1` instead of `Key1`.
+    pub fn label(&self) -> String {
+        let name = format!("{:?}", self.key);
+        let name = name.strip_prefix("Key").unwrap_or(&name);
+        format!("{}{}", self.modifier_prefix(), name)
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}{:?}", self.modifier_prefix(), self.key)
+    }
+}
+
+/// The key bound to every action.
+#[derive(Clone, PartialEq)]
+pub struct Bindings {
+    keys: Vec<KeyBinding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: Action::ALL.iter().map(|action| action.default_binding()).collect(),
+        }
+    }
+}
+
+impl Bindings {
+    fn index(action: Action) -> usize {
+        Action::ALL.iter().position(|a| *a == action).unwrap()
+    }
+
+    pub fn get(&self, action: Action) -> KeyBinding {
+        self.keys[Bindings::index(action)]
+    }
+
+    pub fn set(&mut self, action: Action, binding: KeyBinding) {
+        self.keys[Bindings::index(action)] = binding;
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.get(action).pressed() || action.alternate_binding().is_some_and(|binding| binding.pressed())
+    }
+
+    pub fn down(&self, action: Action) -> bool {
+        self.get(action).down()
+    }
+
+    /// Other actions in the same group that use the same key as `action`.
+    pub fn conflicts(&self, action: Action) -> Vec<Action> {
+        Action::ALL
+            .iter()
+            .filter(|other| {
+                let binding = self.get(action);
+                action.conflicts_with(other)
+                    && (self.get(**other) == binding || other.alternate_binding() == Some(binding))
+            })
+            .copied()
+            .collect()
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use rcmmm::Grid;
+
+// States remembered for spotting repeats. Once full the memory starts over, so
+// long runs stay cheap at the cost of missing cycles longer than this
+const MAX_STATES: usize = 100_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cycle {
+    /// No state has come back yet.
+    Running,
+    /// The grid has not changed since tick `since`.
+    Stable { since: u64 },
+    /// The grid at tick `first_repeat` is the same as `period` ticks earlier,
+    /// so it keeps looping from there.
+    Periodic { period: u64, first_repeat: u64 },
+}
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Cycle::Running => write!(f, "Running"),
+            Cycle::Stable { since } => write!(f, "Stable since tick {}", since),
+            Cycle::Periodic { period, first_repeat } => {
+                write!(f, "Loops every {} ticks, first repeat at tick {}", period, first_repeat)
+            }
+        }
+    }
+}
+
+/// Hash of everything that decides how a grid plays out.
+pub fn state_hash(grid: &Grid) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    grid.cells.hash(&mut hasher);
+    grid.wrap.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Watches a grid tick by tick for states it has been in before. The grid
+/// only depends on its last state, so the first repeat means it loops forever.
+pub struct CycleDetector {
+    // State hash and the tick it was seen at
+    seen: HashMap<u64, u64>,
+    tick: u64,
+    cycle: Cycle,
+}
+
+impl CycleDetector {
+    pub fn new(grid: &Grid) -> CycleDetector {
+        let mut detector = CycleDetector {
+            seen: HashMap::new(),
+            tick: 0,
+            cycle: Cycle::Running,
+        };
+        detector.reset(grid);
+        detector
+    }
+
+    /// Starts over from `grid` as tick 0, e.g. after the grid was edited.
+    pub fn reset(&mut self, grid: &Grid) {
+        self.start_at(grid, 0);
+    }
+
+    /// Starts over from `grid` as tick `tick`, e.g. when playing on from an
+    /// earlier point of the timeline.
+    pub fn start_at(&mut self, grid: &Grid, tick: u64) {
+        self.seen.clear();
+        self.seen.insert(state_hash(grid), tick);
+        self.tick = tick;
+        self.cycle = Cycle::Running;
+    }
+
+    /// Records the state after one more tick.
+    pub fn observe(&mut self, grid: &Grid) -> Cycle {
+        self.tick += 1;
+        if self.cycle != Cycle::Running {
+            return self.cycle;
+        }
+
+        let hash = state_hash(grid);
+        if let Some(&earlier) = self.seen.get(&hash) {
+            let period = self.tick - earlier;
+            self.cycle = if period == 1 {
+                Cycle::Stable { since: earlier }
+            } else {
+                Cycle::Periodic { period, first_repeat: self.tick }
+            };
+            return self.cycle;
+        }
+
+        if self.seen.len() >= MAX_STATES {
+            self.seen.clear();
+        }
+        self.seen.insert(hash, self.tick);
+        self.cycle
+    }
+
+    pub fn cycle(&self) -> Cycle {
+        self.cycle
+    }
+
+    /// Ticks observed since the last reset.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rcmmm::asciigrid;
+
+    use super::*;
+
+    // Steps the grid until the detector sees a cycle, or gives up
+    fn run(grid: &mut Grid, ticks: u64) -> Cycle {
+        let mut detector = CycleDetector::new(grid);
+        for _ in 0..ticks {
+            grid.step();
+            if detector.observe(grid) != Cycle::Running {
+                break;
+            }
+        }
+        detector.cycle()
+    }
+
+    #[test]
+    fn grid_that_stops_changing_is_stable() {
+        let mut grid = asciigrid::decode("> . .").unwrap();
+        // The mover reaches the edge at tick 2 and stays there
+        assert_eq!(run(&mut grid, 10), Cycle::Stable { since: 2 });
+    }
+
+    #[test]
+    fn mover_going_round_a_wrapping_grid_loops() {
+        let mut grid = asciigrid::decode("> . .\n. . .").unwrap();
+        grid.wrap = true;
+        assert_eq!(run(&mut grid, 10), Cycle::Periodic { period: 3, first_repeat: 3 });
+    }
+
+    #[test]
+    fn running_until_a_state_comes_back() {
+        let mut grid = asciigrid::decode("> . . . .").unwrap();
+        grid.wrap = true;
+        let mut detector = CycleDetector::new(&grid);
+        for _ in 0..4 {
+            grid.step();
+            assert_eq!(detector.observe(&grid), Cycle::Running);
+        }
+        grid.step();
+        assert_eq!(detector.observe(&grid), Cycle::Periodic { period: 5, first_repeat: 5 });
+        assert_eq!(detector.tick(), 5);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use rcmmm::{Event, Grid};
+
+// Ticks the counts cover
+pub const WINDOW: usize = 64;
+
+/// What the heatmap counts for each square.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeatmapMode {
+    // A cell moved in or out, was generated, destroyed or turned there
+    Activity,
+    // A cell was there at the end of the tick
+    Occupancy,
+}
+
+impl HeatmapMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapMode::Activity => "Activity",
+            HeatmapMode::Occupancy => "Occupancy",
+        }
+    }
+}
+
+// The squares counted in one tick, as indices into the totals
+struct Sample {
+    tick: u64,
+    changed: Vec<usize>,
+    occupied: Vec<usize>,
+}
+
+/// How often each square of a grid changed or was occupied over the last
+/// `WINDOW` ticks.
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    samples: VecDeque<Sample>,
+    changed: Vec<u32>,
+    occupied: Vec<u32>,
+}
+
+impl Heatmap {
+    pub fn new(grid: &Grid) -> Heatmap {
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+        Heatmap {
+            width,
+            height,
+            samples: VecDeque::new(),
+            changed: vec![0; width * height],
+            occupied: vec![0; width * height],
+        }
+    }
+
+    /// Forgets every tick, e.g. after the grid was edited or resized.
+    pub fn reset(&mut self, grid: &Grid) {
+        *self = Heatmap::new(grid);
+    }
+
+    /// Counts the squares the events of tick `tick` touched and the ones that
+    /// are occupied now.
+    pub fn record(&mut self, grid: &Grid, tick: u64) {
+        if (grid.width() as usize, grid.height() as usize) != (self.width, self.height) {
+            self.reset(grid);
+        }
+        let index = |(x, y): (usize, usize)| x * self.height + y;
+
+        let mut changed = Vec::new();
+        for event in &grid.events {
+            match *event {
+                Event::Moved { from, to } => changed.extend([index(from), index(to)]),
+                Event::Generated { at, .. } | Event::Rotated { at, .. } | Event::Destroyed { at } => {
+                    changed.push(index(at))
+                }
+                Event::GenerationBlocked { .. } | Event::PushBlocked { .. } => {}
+            }
+        }
+        // A square counts once per tick, however much happened in it
+        changed.sort_unstable();
+        changed.dedup();
+        let occupied: Vec<usize> = grid
+            .cells
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+
+        for &i in &changed {
+            self.changed[i] += 1;
+        }
+        for &i in &occupied {
+            self.occupied[i] += 1;
+        }
+        self.samples.push_back(Sample { tick, changed, occupied });
+        if self.samples.len() > WINDOW {
+            self.pop(false);
+        }
+    }
+
+    /// Forgets the ticks after `tick`, e.g. when playing on from an earlier one.
+    pub fn truncate(&mut self, tick: u64) {
+        while self.samples.back().is_some_and(|sample| sample.tick > tick) {
+            self.pop(true);
+        }
+    }
+
+    // Removes the oldest or the newest sample from the totals
+    fn pop(&mut self, newest: bool) {
+        let sample = if newest { self.samples.pop_back() } else { self.samples.pop_front() };
+        if let Some(sample) = sample {
+            for i in sample.changed {
+                self.changed[i] -= 1;
+            }
+            for i in sample.occupied {
+                self.occupied[i] -= 1;
+            }
+        }
+    }
+
+    /// Number of ticks the counts currently cover.
+    pub fn ticks(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// In how many of the covered ticks the square changed or was occupied.
+    pub fn count(&self, mode: HeatmapMode, x: usize, y: usize) -> u32 {
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        let counts = match mode {
+            HeatmapMode::Activity => &self.changed,
+            HeatmapMode::Occupancy => &self.occupied,
+        };
+        counts[x * self.height + y]
+    }
+}
@@ -0,0 +1,41 @@
+// Undo history for the editor. Each entry is the level code of the grid
+// before an edit, which keeps snapshots small even on big grids.
+const LIMIT: usize = 100;
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<String>,
+    redo: Vec<String>,
+}
+
+impl History {
+    pub fn new() -> History {
+        History::default()
+    }
+
+    /// Remembers the state from before an edit.
+    pub fn record(&mut self, state: String) {
+        if self.undo.last() == Some(&state) {
+            return;
+        }
+        self.undo.push(state);
+        if self.undo.len() > LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the state to go back to, given the current one.
+    pub fn undo(&mut self, current: String) -> Option<String> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        Some(state)
+    }
+
+    /// Returns the state that was undone last, given the current one.
+    pub fn redo(&mut self, current: String) -> Option<String> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        Some(state)
+    }
+}
@@ -0,0 +1,40 @@
+use std::{fs, io, path::PathBuf};
+
+// Saved levels are plain text files holding a level code
+const LEVELS_DIR: &str = "levels";
+const EXTENSION: &str = "txt";
+
+fn level_path(name: &str) -> PathBuf {
+    PathBuf::from(LEVELS_DIR).join(format!("{}.{}", name, EXTENSION))
+}
+
+/// Level names become file names, so only allow a safe set of characters.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_')
+}
+
+/// Names of all saved levels, sorted alphabetically.
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = match fs::read_dir(LEVELS_DIR) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+            .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    names.sort();
+    names
+}
+
+pub fn save(name: &str, code: &str) -> io::Result<()> {
+    fs::create_dir_all(LEVELS_DIR)?;
+    fs::write(level_path(name), code)
+}
+
+pub fn load(name: &str) -> io::Result<String> {
+    fs::read_to_string(level_path(name))
+}
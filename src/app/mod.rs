@@ -0,0 +1,11 @@
+pub mod settings;
+pub mod controls;
+pub mod levels;
+pub mod templates;
+pub mod history;
+pub mod cycles;
+pub mod stats;
+pub mod timeline;
+pub mod breakpoints;
+pub mod heatmap;
+pub mod trails;
@@ -0,0 +1,117 @@
+use std::{fs, io, ops::RangeInclusive, path::PathBuf};
+
+use super::controls::{Action, Bindings, KeyBinding};
+
+// Settings are stored as `key = value` lines in the user's config directory,
+// e.g. ~/.config/rcmmm/settings.txt on Linux. Unknown keys are ignored and
+// values that don't parse fall back to their defaults. Key bindings are stored
+// as `bind.<action> = <key>` lines.
+const APP_DIR: &str = "rcmmm";
+const FILE_NAME: &str = "settings.txt";
+
+// Window sizes the settings screen offers, larger or smaller ones are clamped
+pub const WINDOW_WIDTHS: RangeInclusive<i32> = 800..=3840;
+pub const WINDOW_HEIGHTS: RangeInclusive<i32> = 600..=2160;
+
+#[derive(Clone, PartialEq)]
+pub struct Settings {
+    pub pan_speed: f32,
+    pub window_width: i32,
+    pub window_height: i32,
+    // Frames between simulation ticks
+    pub tick_interval: u32,
+    pub show_fps: bool,
+    // Slide cells between squares instead of jumping at each tick
+    pub smooth_movement: bool,
+    // Pause the game once the grid stops changing
+    pub pause_when_stable: bool,
+    pub bindings: Bindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            pan_speed: 7.5,
+            window_width: 1600,
+            window_height: 900,
+            tick_interval: 10,
+            show_fps: true,
+            smooth_movement: true,
+            pause_when_stable: false,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(APP_DIR).join(FILE_NAME))
+    }
+
+    /// Loads the settings file, or the defaults if there is none yet.
+    pub fn load() -> Settings {
+        match Settings::path().and_then(|path| fs::read_to_string(path).ok()) {
+            Some(text) => Settings::parse(&text),
+            None => Settings::default(),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Settings::path().ok_or(io::Error::new(io::ErrorKind::NotFound, "No config directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_text())
+    }
+
+    pub fn parse(text: &str) -> Settings {
+        let mut settings = Settings::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "pan_speed" => settings.pan_speed = value.parse().unwrap_or(settings.pan_speed),
+                "window_width" => {
+                    let width: i32 = value.parse().unwrap_or(settings.window_width);
+                    settings.window_width = width.clamp(*WINDOW_WIDTHS.start(), *WINDOW_WIDTHS.end());
+                }
+                "window_height" => {
+                    let height: i32 = value.parse().unwrap_or(settings.window_height);
+                    settings.window_height = height.clamp(*WINDOW_HEIGHTS.start(), *WINDOW_HEIGHTS.end());
+                }
+                "tick_interval" => settings.tick_interval = value.parse().unwrap_or(settings.tick_interval).max(1),
+                "show_fps" => settings.show_fps = value.parse().unwrap_or(settings.show_fps),
+                "smooth_movement" => settings.smooth_movement = value.parse().unwrap_or(settings.smooth_movement),
+                "pause_when_stable" => settings.pause_when_stable = value.parse().unwrap_or(settings.pause_when_stable),
+                key => {
+                    let action = key.strip_prefix("bind.").and_then(Action::from_id);
+                    if let (Some(action), Some(binding)) = (action, KeyBinding::parse(value)) {
+                        settings.bindings.set(action, binding);
+                    }
+                }
+            }
+        }
+        settings
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# rcmmm settings\n");
+        text.push_str(&format!("pan_speed = {}\n", self.pan_speed));
+        text.push_str(&format!("window_width = {}\n", self.window_width));
+        text.push_str(&format!("window_height = {}\n", self.window_height));
+        text.push_str(&format!("tick_interval = {}\n", self.tick_interval));
+        text.push_str(&format!("show_fps = {}\n", self.show_fps));
+        text.push_str(&format!("smooth_movement = {}\n", self.smooth_movement));
+        text.push_str(&format!("pause_when_stable = {}\n", self.pause_when_stable));
+        for action in Action::ALL {
+            text.push_str(&format!("bind.{} = {}\n", action.id(), self.bindings.get(action)));
+        }
+        text
+    }
+}
@@ -0,0 +1,100 @@
+use std::{collections::VecDeque, fs, io, path::PathBuf};
+
+use rcmmm::{cellkind, levelcode::MAX_CELL_ID, Event, Grid};
+
+// Ticks kept, older ones are dropped
+const MAX_TICKS: usize = 10_000;
+// Exported statistics go here as `<level name>.csv`
+const STATS_DIR: &str = "stats";
+
+/// What a grid looked like after one tick and how busy the tick was.
+#[derive(Clone)]
+pub struct TickStats {
+    pub tick: u64,
+    // Number of cells of each kind, indexed by id
+    pub counts: Vec<usize>,
+    pub moves: usize,
+    pub generated: usize,
+    pub destroyed: usize,
+}
+
+impl TickStats {
+    pub fn count(&self, id: u8) -> usize {
+        self.counts.get(id as usize).copied().unwrap_or(0)
+    }
+}
+
+/// Statistics of the recent ticks of a grid.
+#[derive(Default)]
+pub struct Stats {
+    ticks: VecDeque<TickStats>,
+}
+
+impl Stats {
+    pub fn new() -> Stats {
+        Stats::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.ticks.clear();
+    }
+
+    /// Records the grid as it is after tick `tick`, with the events of that tick.
+    pub fn record(&mut self, grid: &Grid, tick: u64) {
+        let mut counts = vec![0; MAX_CELL_ID as usize + 1];
+        for cell in grid.cells.iter().flatten() {
+            counts[cell.id as usize] += 1;
+        }
+        let count_events = |matches: fn(&Event) -> bool| grid.events.iter().filter(|event| matches(event)).count();
+        self.ticks.push_back(TickStats {
+            tick,
+            counts,
+            moves: count_events(|event| matches!(event, Event::Moved { .. })),
+            generated: count_events(|event| matches!(event, Event::Generated { .. })),
+            destroyed: count_events(|event| matches!(event, Event::Destroyed { .. })),
+        });
+        if self.ticks.len() > MAX_TICKS {
+            self.ticks.pop_front();
+        }
+    }
+
+    /// Forgets the ticks after `tick`, e.g. when playing on from an earlier one.
+    pub fn truncate(&mut self, tick: u64) {
+        while self.ticks.back().is_some_and(|stats| stats.tick > tick) {
+            self.ticks.pop_back();
+        }
+    }
+
+    pub fn ticks(&self) -> &VecDeque<TickStats> {
+        &self.ticks
+    }
+
+    /// One line per tick with the moves, generated and destroyed cells and the
+    /// number of cells of every registered kind.
+    pub fn to_csv(&self) -> String {
+        let kinds: Vec<_> = cellkind::all().into_iter().filter(|kind| kind.id() != 0).collect();
+        let mut csv = String::from("tick,moves,generated,destroyed");
+        for kind in &kinds {
+            // Names come from cell files, so keep them from breaking the columns
+            csv.push_str(&format!(",{}", kind.name().replace([',', '"', '\n'], " ")));
+        }
+        csv.push('\n');
+        for stats in &self.ticks {
+            csv.push_str(&format!("{},{},{},{}", stats.tick, stats.moves, stats.generated, stats.destroyed));
+            for kind in &kinds {
+                csv.push_str(&format!(",{}", stats.count(kind.id())));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Writes the CSV to the stats directory, named after the level. Returns
+    /// the path of the file.
+    pub fn export(&self, name: &str) -> io::Result<PathBuf> {
+        fs::create_dir_all(STATS_DIR)?;
+        let path = PathBuf::from(STATS_DIR).join(format!("{}.csv", name));
+        fs::write(&path, self.to_csv())?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,65 @@
+use rcmmm::{Cell, CellKind, Directions, Grid, WallCell};
+
+/// Starting layouts offered when creating a new level.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Template {
+    Empty,
+    WalledBorder,
+    // A walled border split by a wall with a gap in the middle, leaving a
+    // build area on the left and a playfield on the right
+    PuzzleSkeleton,
+}
+
+impl Template {
+    pub const ALL: [Template; 3] = [Template::Empty, Template::WalledBorder, Template::PuzzleSkeleton];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Template::Empty => "Empty",
+            Template::WalledBorder => "Walled border",
+            Template::PuzzleSkeleton => "Puzzle skeleton",
+        }
+    }
+
+    /// The smallest grid the template fits in.
+    pub fn min_size(&self) -> u32 {
+        match self {
+            Template::Empty => 1,
+            Template::WalledBorder => 3,
+            Template::PuzzleSkeleton => 7,
+        }
+    }
+
+    /// Adds the template's walls. Fails if the grid is smaller than `min_size`.
+    pub fn apply(&self, grid: &mut Grid) -> Result<(), String> {
+        let w = grid.cells.len() as u32;
+        let h = grid.cells[0].len() as u32;
+        let mut walls = Vec::new();
+
+        if *self == Template::WalledBorder || *self == Template::PuzzleSkeleton {
+            for x in 0..w {
+                walls.push((x, 0));
+                walls.push((x, h - 1));
+            }
+            for y in 1..h - 1 {
+                walls.push((0, y));
+                walls.push((w - 1, y));
+            }
+        }
+
+        if *self == Template::PuzzleSkeleton {
+            let divider = w / 3;
+            let (gap_start, gap_end) = (h / 2 - 1, h / 2 + 1);
+            for y in 1..h - 1 {
+                if y < gap_start || y > gap_end {
+                    walls.push((divider, y));
+                }
+            }
+        }
+
+        for (x, y) in walls {
+            grid.set_cell(x, y, Cell::new(WallCell.id(), Directions::Right))?;
+        }
+        Ok(())
+    }
+}
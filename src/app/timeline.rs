@@ -0,0 +1,220 @@
+use std::{collections::VecDeque, mem::size_of};
+
+use rcmmm::{Cell, Event, Grid};
+
+// At least every this many ticks the whole grid is stored, in between only
+// the squares that changed. Going back to a tick replays at most this many
+// deltas
+const KEYFRAME_INTERVAL: u64 = 64;
+// Ticks and bytes kept. Older ticks are dropped a keyframe at a time once
+// either is exceeded
+const MAX_TICKS: usize = 4096;
+const MAX_BYTES: usize = 64 * 1024 * 1024;
+// Events kept per tick, busy grids can have one for nearly every square
+const MAX_EVENTS: usize = 256;
+
+struct Frame {
+    // The whole grid on keyframes
+    keyframe: Option<Vec<Vec<Cell>>>,
+    // Squares that changed since the previous tick
+    changes: Vec<(usize, usize, Cell)>,
+    events: Vec<Event>,
+}
+
+impl Frame {
+    // Roughly the memory the frame holds on to
+    fn bytes(&self) -> usize {
+        let keyframe = self.keyframe.as_ref().map_or(0, |cells| cells.len() * cells[0].len() * size_of::<Cell>());
+        size_of::<Frame>()
+            + keyframe
+            + self.changes.capacity() * size_of::<(usize, usize, Cell)>()
+            + self.events.capacity() * size_of::<Event>()
+    }
+}
+
+/// The recent past of a grid, so earlier ticks can be looked at again and
+/// played on from.
+pub struct Timeline {
+    // Tick of the first frame, which is always a keyframe
+    first_tick: u64,
+    frames: VecDeque<Frame>,
+    // Sum of the frames' `bytes`
+    bytes: usize,
+    // The grid at the last tick
+    last: Vec<Vec<Cell>>,
+}
+
+impl Timeline {
+    /// Starts a timeline at tick 0 with the grid as it is now.
+    pub fn new(grid: &Grid) -> Timeline {
+        let mut timeline = Timeline {
+            first_tick: 0,
+            frames: VecDeque::new(),
+            bytes: 0,
+            last: Vec::new(),
+        };
+        timeline.reset(grid);
+        timeline
+    }
+
+    /// Forgets everything and starts over from `grid` as tick 0.
+    pub fn reset(&mut self, grid: &Grid) {
+        self.first_tick = 0;
+        self.frames.clear();
+        self.bytes = 0;
+        self.push(Frame {
+            keyframe: Some(grid.cells.clone()),
+            changes: Vec::new(),
+            events: Vec::new(),
+        });
+        self.last = grid.cells.clone();
+    }
+
+    pub fn first_tick(&self) -> u64 {
+        self.first_tick
+    }
+
+    pub fn last_tick(&self) -> u64 {
+        self.first_tick + self.frames.len() as u64 - 1
+    }
+
+    fn push(&mut self, frame: Frame) {
+        self.bytes += frame.bytes();
+        self.frames.push_back(frame);
+    }
+
+    /// Stores the grid after it stepped once more.
+    pub fn record(&mut self, grid: &Grid) {
+        let since_keyframe = self.frames.iter().rev().position(|frame| frame.keyframe.is_some()).unwrap_or(0) + 1;
+        let mut changes = Vec::new();
+        for (x, (column, last_column)) in grid.cells.iter().zip(&self.last).enumerate() {
+            for (y, (cell, last_cell)) in column.iter().zip(last_column).enumerate() {
+                if cell != last_cell {
+                    changes.push((x, y, *cell));
+                }
+            }
+        }
+        // A delta that would take more room than the whole grid isn't worth it
+        let squares = grid.cells.len() * grid.cells[0].len();
+        let delta_bytes = changes.len() * size_of::<(usize, usize, Cell)>();
+        let keyframe = (since_keyframe as u64 >= KEYFRAME_INTERVAL || delta_bytes >= squares * size_of::<Cell>())
+            .then(|| grid.cells.clone());
+        if keyframe.is_some() {
+            changes = Vec::new();
+        }
+        changes.shrink_to_fit();
+        let events = grid.events.iter().take(MAX_EVENTS).copied().collect();
+        self.push(Frame { keyframe, changes, events });
+        self.last = grid.cells.clone();
+
+        while self.frames.len() > MAX_TICKS || self.bytes > MAX_BYTES {
+            // Drop the oldest keyframe and the deltas after it
+            let Some(drop) = self.frames.iter().skip(1).position(|frame| frame.keyframe.is_some()) else {
+                break;
+            };
+            for frame in self.frames.drain(..drop + 1) {
+                self.bytes -= frame.bytes();
+            }
+            self.first_tick += drop as u64 + 1;
+        }
+    }
+
+    /// The cells and the events of `tick`, if it is still stored. Only the
+    /// first `MAX_EVENTS` events of a tick are kept.
+    pub fn state(&self, tick: u64) -> Option<(Vec<Vec<Cell>>, &[Event])> {
+        if tick < self.first_tick || tick > self.last_tick() {
+            return None;
+        }
+        let index = (tick - self.first_tick) as usize;
+        let start = (0..=index).rev().find(|i| self.frames[*i].keyframe.is_some())?;
+        let mut cells = self.frames[start].keyframe.clone()?;
+        for frame in self.frames.range(start + 1..=index) {
+            for (x, y, cell) in &frame.changes {
+                cells[*x][*y] = *cell;
+            }
+        }
+        Some((cells, &self.frames[index].events))
+    }
+
+    /// Forgets the ticks after `tick`, so the grid can play on from there.
+    pub fn truncate(&mut self, tick: u64) {
+        if tick < self.first_tick || tick >= self.last_tick() {
+            return;
+        }
+        if let Some((cells, _)) = self.state(tick) {
+            for frame in self.frames.drain((tick - self.first_tick) as usize + 1..) {
+                self.bytes -= frame.bytes();
+            }
+            self.last = cells;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rcmmm::{asciigrid, Directions};
+
+    use super::*;
+
+    #[test]
+    fn earlier_ticks_come_back_from_keyframes_and_deltas() {
+        let mut grid = asciigrid::decode("> . . . . . .\n. . # . . . v\n. . . . . . .").unwrap();
+        grid.wrap = true;
+        let mut timeline = Timeline::new(&grid);
+        let mut states = vec![grid.cells.clone()];
+        for _ in 0..200 {
+            grid.step();
+            timeline.record(&grid);
+            states.push(grid.cells.clone());
+        }
+
+        assert_eq!((timeline.first_tick(), timeline.last_tick()), (0, 200));
+        for (tick, cells) in states.iter().enumerate() {
+            assert_eq!(&timeline.state(tick as u64).unwrap().0, cells, "tick {}", tick);
+        }
+        assert!(timeline.state(201).is_none());
+
+        timeline.truncate(70);
+        assert_eq!(timeline.last_tick(), 70);
+        grid.cells = states[70].clone();
+        grid.step();
+        timeline.record(&grid);
+        assert_eq!(timeline.state(71).unwrap().0, states[71]);
+    }
+
+    #[test]
+    fn old_ticks_are_dropped_after_max_ticks() {
+        let mut grid = asciigrid::decode("> . .").unwrap();
+        grid.wrap = true;
+        let mut timeline = Timeline::new(&grid);
+        for _ in 0..5000 {
+            grid.step();
+            timeline.record(&grid);
+        }
+
+        assert_eq!(timeline.last_tick(), 5000);
+        assert!(timeline.frames.len() <= MAX_TICKS);
+        // Whole keyframe segments go, so the first kept tick is a keyframe
+        assert!(timeline.first_tick() > 0);
+        assert!(timeline.frames[0].keyframe.is_some());
+        assert!(timeline.state(timeline.first_tick() - 1).is_none());
+        assert!(timeline.state(timeline.first_tick()).is_some());
+    }
+
+    #[test]
+    fn old_ticks_are_dropped_after_max_bytes() {
+        let mut grid = Grid::new(200, 200).unwrap();
+        let mut timeline = Timeline::new(&grid);
+        // Every square changes every tick, so each tick stores a whole keyframe
+        for tick in 0..1000 {
+            let cell = if tick % 2 == 0 { Cell::new(2, Directions::Right) } else { Cell::empty() };
+            grid.cells = vec![vec![cell; 200]; 200];
+            timeline.record(&grid);
+        }
+
+        assert_eq!(timeline.last_tick(), 1000);
+        assert!(timeline.bytes <= MAX_BYTES);
+        assert!(timeline.frames.len() < 1000);
+        assert_eq!(timeline.bytes, timeline.frames.iter().map(Frame::bytes).sum::<usize>());
+    }
+}
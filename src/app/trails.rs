@@ -0,0 +1,58 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rcmmm::Grid;
+
+// Ticks a trail takes to fade out
+pub const LENGTH: u64 = 24;
+
+/// Squares a cell passed through, as the tick it got there and the square.
+pub type Trail = VecDeque<(u64, (usize, usize))>;
+
+/// The recent paths of the cells of a grid, followed by their identities.
+#[derive(Default)]
+pub struct Trails {
+    trails: HashMap<u64, Trail>,
+}
+
+impl Trails {
+    pub fn new() -> Trails {
+        Trails::default()
+    }
+
+    /// Forgets every path, e.g. after the grid was edited or the shown tick
+    /// jumped.
+    pub fn clear(&mut self) {
+        self.trails.clear();
+    }
+
+    /// Adds where every cell is after tick `tick`, and drops what has faded.
+    pub fn record(&mut self, grid: &Grid, tick: u64) {
+        let mut alive = HashSet::new();
+        for x in 0..grid.width() as usize {
+            for y in 0..grid.height() as usize {
+                let Some(identity) = grid.identity(x, y) else {
+                    continue;
+                };
+                alive.insert(identity);
+                let trail = self.trails.entry(identity).or_default();
+                if trail.back().is_none_or(|&(_, square)| square != (x, y)) {
+                    trail.push_back((tick, (x, y)));
+                }
+            }
+        }
+
+        self.trails.retain(|identity, trail| {
+            // A step has faded once the square it led to is old enough
+            while trail.len() > 1 && trail[1].0 + LENGTH <= tick {
+                trail.pop_front();
+            }
+            // Cells that are gone leave their trail until it has faded
+            alive.contains(identity) || trail.back().is_some_and(|&(last, _)| last + LENGTH > tick)
+        });
+    }
+
+    /// The paths of the cells that moved, by identity.
+    pub fn trails(&self) -> impl Iterator<Item = (u64, &Trail)> {
+        self.trails.iter().filter(|(_, trail)| trail.len() > 1).map(|(identity, trail)| (*identity, trail))
+    }
+}
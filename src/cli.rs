@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, fs};
+
+use rcmmm::{asciigrid, cellkind, customcell, levelcode, Grid};
+
+use crate::{app::{cycles::{Cycle, CycleDetector}, stats::Stats}, terminal};
+
+// Headless commands, run instead of opening the window:
+//
+//     rcmmm run <level file> [--ticks <n>] [--out <file>] [--ascii] [--stats <file>]
+//
+// `run` simulates the level code in the file for n ticks (100 by default), or
+// until a tick changes nothing, then prints the resulting level code and a
+// summary. With --out the level code goes to that file instead, and with
+// --ascii the grid is written in the `asciigrid` format. --stats writes the
+// statistics of every tick to a CSV file.
+//
+//     rcmmm view <level file> [--ascii]
+//
+// `view` plays the level in the terminal, see `terminal.rs`. --ascii draws it
+// without Unicode characters.
+const USAGE: &str = "Usage: rcmmm run <level file> [--ticks <n>] [--out <file>] [--ascii] [--stats <file>]
+       rcmmm view <level file> [--ascii]";
+const DEFAULT_TICKS: u32 = 100;
+
+struct RunOptions {
+    level: String,
+    ticks: u32,
+    out: Option<String>,
+    ascii: bool,
+    stats: Option<String>,
+}
+
+impl RunOptions {
+    fn parse(args: &[String]) -> Result<RunOptions, String> {
+        let mut level = None;
+        let mut ticks = DEFAULT_TICKS;
+        let mut out = None;
+        let mut ascii = false;
+        let mut stats = None;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--ticks" => {
+                    let value = args.next().ok_or("--ticks needs a number")?;
+                    ticks = value.parse().map_err(|_| format!("Invalid number of ticks: {}", value))?;
+                }
+                "--out" => out = Some(args.next().ok_or("--out needs a file")?.clone()),
+                "--ascii" => ascii = true,
+                "--stats" => stats = Some(args.next().ok_or("--stats needs a file")?.clone()),
+                _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+                _ if level.is_none() => level = Some(arg.clone()),
+                _ => return Err(format!("Unexpected argument: {}", arg)),
+            }
+        }
+
+        Ok(RunOptions {
+            level: level.ok_or("Missing level file")?,
+            ticks,
+            out,
+            ascii,
+            stats,
+        })
+    }
+}
+
+/// Runs the command in `args` (without the program name). Returns None if
+/// the arguments are not a command, so the game should start instead.
+pub fn run(args: &[String]) -> Option<Result<(), String>> {
+    let (command, rest) = args.split_first()?;
+    match command.as_str() {
+        "run" => Some(RunOptions::parse(rest).map_err(|err| format!("{}\n{}", err, USAGE)).and_then(run_level)),
+        "view" => Some(view_level(rest)),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Some(Ok(()))
+        }
+        _ => None,
+    }
+}
+
+fn load_level(path: &str) -> Result<Grid, String> {
+    for error in customcell::load_all() {
+        eprintln!("Could not load cell {}", error);
+    }
+    let code = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+    levelcode::decode(&code).map_err(|err| format!("{}: {}", path, err))
+}
+
+fn view_level(args: &[String]) -> Result<(), String> {
+    let (level, ascii) = match args {
+        [level] => (level, false),
+        [level, flag] | [flag, level] if flag == "--ascii" => (level, true),
+        _ => return Err(USAGE.to_string()),
+    };
+    let grid = load_level(level)?;
+    terminal::view(grid, ascii).map_err(|err| err.to_string())
+}
+
+fn run_level(options: RunOptions) -> Result<(), String> {
+    let mut grid = load_level(&options.level)?;
+
+    let mut cycles = CycleDetector::new(&grid);
+    let mut stats = Stats::new();
+    stats.record(&grid, 0);
+    for _ in 0..options.ticks {
+        grid.step();
+        let cycle = cycles.observe(&grid);
+        stats.record(&grid, cycles.tick());
+        if let Cycle::Stable { .. } = cycle {
+            break;
+        }
+    }
+    for error in cellkind::all().iter().filter_map(|kind| kind.error()) {
+        eprintln!("Script error in {}", error);
+    }
+    if let Some(path) = &options.stats {
+        fs::write(path, stats.to_csv()).map_err(|err| format!("{}: {}", path, err))?;
+    }
+
+    let text = if options.ascii {
+        asciigrid::encode(&grid)
+    } else {
+        format!("{}\n", levelcode::encode(&grid))
+    };
+    match &options.out {
+        Some(path) => fs::write(path, text).map_err(|err| format!("{}: {}", path, err))?,
+        None => print!("{}", text),
+    }
+    print_summary(&grid, &cycles);
+    Ok(())
+}
+
+fn print_summary(grid: &Grid, cycles: &CycleDetector) {
+    println!("Ran {} ticks", cycles.tick());
+    println!("{}", cycles.cycle());
+
+    let mut counts = BTreeMap::new();
+    for (x, y) in grid.occupied() {
+        if let Some(cell) = grid.cell(x, y) {
+            *counts.entry(cell.id).or_insert(0) += 1;
+        }
+    }
+    println!("{} cells on a {}x{} grid", counts.values().sum::<u32>(), grid.width(), grid.height());
+    for (id, count) in counts {
+        let name = cellkind::get(id).map_or("Unknown".to_string(), |kind| kind.name().to_string());
+        println!("  {}: {}", name, count);
+    }
+}
@@ -0,0 +1,35 @@
+//! The simulator behind the game, for tools that want to run grids themselves.
+//!
+//! ```
+//! use rcmmm::{Cell, Directions, Grid};
+//!
+//! let mut grid = Grid::new(8, 1)?;
+//! grid.set_cell(0, 0, Cell::new(1, Directions::Right))?;
+//! for _ in 0..3 {
+//!     grid.step();
+//! }
+//! assert_eq!(grid.cell(3, 0), Some(Cell::new(1, Directions::Right)));
+//! # Ok::<(), String>(())
+//! ```
+//!
+//! Custom cells from the cells directory are only known after
+//! `customcell::load_all` has been called.
+
+mod utils;
+
+pub use utils::{
+    anchor::Anchor,
+    asciigrid,
+    cellkind::{self, CellKind},
+    cells::Cell,
+    customcell,
+    directions::{Directions, Side},
+    emptycell::EmptyCell,
+    events::Event,
+    generatorcell::GeneratorCell,
+    grid::{Grid, Step, MAX_SIZE},
+    levelcode,
+    movercell::MoverCell,
+    pushcell::PushCell,
+    wallcell::WallCell,
+};
@@ -1,60 +1,56 @@
+use std::{env, process};
+
 use macroquad::prelude::*;
-use screens::{gamescreen::GameScreen, titlescreen::TitleScreen};
+use rcmmm::customcell;
+
+use app::settings::Settings;
+use screens::{screen::ScreenContext, screenmanager::ScreenManager, titlescreen::TitleScreen};
 
+mod app;
+mod cli;
 mod screens;
-mod utils;
+mod terminal;
 mod widgets;
 
 fn window_conf() -> Conf {
+    let settings = Settings::load();
     Conf {
         window_title: "cell_machine_mystic_mod.rs".to_string(),
-        window_width: 1600,
-        window_height: 900,
+        window_width: settings.window_width,
+        window_height: settings.window_height,
         ..Default::default()
     }
 }
 
-async fn game_loop() {
-    let mut game_screen = GameScreen::new(50, 50).await;
-
-    let mut tick = 0;
-    loop {
-        game_screen.update(tick).await;
-        game_screen.draw(tick).await;
-
-        tick += 1;
-        next_frame().await
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match cli::run(&args) {
+        Some(Ok(())) => {}
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+        None => macroquad::Window::from_config(window_conf(), game()),
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let mut title_screen = TitleScreen::new();
-    let mut selected_option;
+async fn game() {
+    for error in customcell::load_all() {
+        eprintln!("Could not load cell {}", error);
+    }
+
+    let context = ScreenContext {
+        settings: Settings::load(),
+    };
+    let mut screens = ScreenManager::new(Box::new(TitleScreen::new()), context);
 
     loop {
-        selected_option = title_screen.update();
-        title_screen.draw();
-
-        if let Some(_option) = &selected_option {
+        screens.update().await;
+        if screens.is_empty() {
             break;
         }
+        screens.draw().await;
 
         next_frame().await
     }
-
-    match selected_option.unwrap().as_str() {
-        "new" => {
-            game_loop().await;
-        }
-        "load" => {
-            println!("Load Game");
-        }
-        "exit" => {
-            println!("Exit");
-        }
-        _ => {
-            println!("Unknown Option");
-        }
-    }
 }
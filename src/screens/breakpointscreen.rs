@@ -0,0 +1,180 @@
+use macroquad::prelude::*;
+
+use rcmmm::Cell;
+
+use crate::app::{breakpoints::Condition, controls::{Action, Bindings}};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::screen::{Screen, ScreenContext, ScreenFuture, ScreenResult, Transition};
+
+const PANEL_WIDTH: f32 = 640.0;
+const SPACING: f32 = 48.0;
+const LIST_ROWS: usize = 5;
+
+/// Overlay for adding and removing the breakpoints of the game screen. Opened
+/// with a square when the user right-clicks the grid.
+pub struct BreakpointScreen {
+    ui: Ui,
+    conditions: Vec<Condition>,
+    square: Option<(u32, u32)>,
+    selected: Option<usize>,
+    tick: String,
+    // Index into `Cell::placeable` of the kind to count
+    kind: usize,
+    more_than: bool,
+    count: String,
+    message: Option<String>,
+}
+
+impl BreakpointScreen {
+    pub fn new(conditions: Vec<Condition>, square: Option<(u32, u32)>) -> BreakpointScreen {
+        let mut ui = Ui::new();
+        ui.set_focus(if square.is_some() { "square" } else { "tick" });
+        BreakpointScreen {
+            ui,
+            conditions,
+            square,
+            selected: None,
+            tick: String::new(),
+            kind: 0,
+            more_than: true,
+            count: String::new(),
+            message: None,
+        }
+    }
+
+    fn toggle(&mut self, condition: Condition) {
+        match self.conditions.iter().position(|other| *other == condition) {
+            Some(i) => {
+                self.conditions.remove(i);
+                self.selected = None;
+            }
+            None => self.conditions.push(condition),
+        }
+        self.message = None;
+    }
+
+    fn add(&mut self, condition: Result<Condition, String>) {
+        match condition {
+            Ok(condition) if self.conditions.contains(&condition) => {
+                self.message = Some("That breakpoint already exists".to_string());
+            }
+            Ok(condition) => {
+                self.conditions.push(condition);
+                self.message = None;
+            }
+            Err(err) => self.message = Some(err),
+        }
+    }
+
+    fn update_form(&mut self, bindings: &Bindings) -> Transition {
+        let rows = 5 + LIST_ROWS;
+        let panel_height = ROW_HEIGHT + 16.0 + SPACING * rows as f32;
+        let panel = Rect::new(
+            (screen_width() - PANEL_WIDTH) / 2.0,
+            (screen_height() - panel_height) / 2.0,
+            PANEL_WIDTH,
+            panel_height,
+        );
+        let row = |i: usize| panel.y + ROW_HEIGHT + 16.0 + i as f32 * SPACING;
+        let full = |i: usize| Rect::new(panel.x + 16.0, row(i), panel.w - 32.0, 40.0);
+        let column = |i: usize, x: f32, w: f32| Rect::new(panel.x + 16.0 + x, row(i), w, 40.0);
+        let add_x = panel.w - 32.0 - 120.0;
+
+        self.ui.begin(bindings);
+        self.ui.panel(panel, "Breakpoints");
+
+        if let Some((x, y)) = self.square {
+            let condition = Condition::Occupied(x, y);
+            let label = if self.conditions.contains(&condition) {
+                format!("Stop breaking when a cell enters {},{}", x, y)
+            } else {
+                format!("Break when a cell enters {},{}", x, y)
+            };
+            if self.ui.button("square", full(0), &label) {
+                self.toggle(condition);
+            }
+        } else {
+            self.ui.label("Right-click a square to break on it", panel.x + 16.0, row(0) + 28.0, 32.0, GRAY);
+        }
+
+        self.ui.label("At tick", panel.x + 16.0, row(1) + 28.0, 32.0, WHITE);
+        self.ui.text_input("tick", column(1, 160.0, add_x - 176.0), &mut self.tick, 9);
+        if self.ui.button("add_tick", column(1, add_x, 120.0), "Add") {
+            let tick = self.tick.trim().parse().map(Condition::Tick).map_err(|_| "Tick must be a whole number".to_string());
+            self.add(tick);
+        }
+
+        let kinds = Cell::placeable();
+        self.kind = self.kind.min(kinds.len() - 1);
+        let kind = kinds[self.kind].kind();
+        if self.ui.button("compare", column(2, 0.0, 144.0), if self.more_than { "More than" } else { "Fewer than" }) {
+            self.more_than = !self.more_than;
+        }
+        self.ui.text_input("count", column(2, 160.0, 96.0), &mut self.count, 6);
+        if self.ui.button("kind", column(2, 272.0, add_x - 288.0), kind.name()) {
+            self.kind = (self.kind + 1) % kinds.len();
+        }
+        if self.ui.button("add_count", column(2, add_x, 120.0), "Add") {
+            let id = kind.id();
+            let more_than = self.more_than;
+            let count = self
+                .count
+                .trim()
+                .parse()
+                .map(|count| if more_than { Condition::MoreThan(id, count) } else { Condition::FewerThan(id, count) })
+                .map_err(|_| "Count must be a whole number".to_string());
+            self.add(count);
+        }
+
+        let mut generator_blocked = self.conditions.contains(&Condition::GeneratorBlocked);
+        if self.ui.toggle("generator", full(3), "Break when a generator is blocked", &mut generator_blocked) {
+            self.toggle(Condition::GeneratorBlocked);
+        }
+
+        let items: Vec<String> = self.conditions.iter().map(|condition| condition.to_string()).collect();
+        let list = Rect::new(panel.x + 16.0, row(4), panel.w - 32.0, ROW_HEIGHT * LIST_ROWS as f32);
+        self.ui.list("list", list, &items, &mut self.selected);
+
+        let button_width = (panel.w - 64.0) / 3.0;
+        let buttons_y = row(4 + LIST_ROWS) + 8.0;
+        let button = |i: usize| Rect::new(panel.x + 16.0 + i as f32 * (button_width + 16.0), buttons_y, button_width, 40.0);
+        if self.ui.button("remove", button(0), "Remove") {
+            if let Some(i) = self.selected.take() {
+                self.conditions.remove(i);
+            }
+        }
+        let done = self.ui.button("done", button(1), "Done");
+        let cancel = self.ui.button("cancel", button(2), "Cancel");
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        self.ui.end();
+
+        if done {
+            return Transition::PopWith(ScreenResult::Breakpoints(self.conditions.clone()));
+        }
+        if cancel || self.ui.pressed(Action::MenuBack) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+}
+
+impl Screen for BreakpointScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move { self.update_form(&context.settings.bindings) })
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+            self.ui.draw();
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,144 @@
+use macroquad::prelude::*;
+
+use crate::app::controls::{Action, ActionGroup, Bindings, KeyBinding};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::screen::{Screen, ScreenContext, ScreenFuture, Transition};
+
+const COLUMN_WIDTH: f32 = 520.0;
+const LABEL_WIDTH: f32 = 300.0;
+const SPACING: f32 = 36.0;
+const BUTTON_HEIGHT: f32 = 32.0;
+const CONFLICT_COLOR: Color = RED;
+
+/// Overlay for rebinding keys. Click an action's key, then press the new key,
+/// optionally with Ctrl held. The bindings are saved together with the other
+/// settings when the settings screen is closed.
+pub struct ControlsScreen {
+    ui: Ui,
+    // Action waiting for its new key
+    rebinding: Option<Action>,
+    message: Option<String>,
+}
+
+impl ControlsScreen {
+    pub fn new() -> ControlsScreen {
+        let mut ui = Ui::new();
+        ui.set_focus(Action::ALL[0].id());
+        ControlsScreen {
+            ui,
+            rebinding: None,
+            message: None,
+        }
+    }
+
+    // Game actions go in the left column, the hotbar and menu ones in the right
+    fn layout() -> Vec<(Action, usize, usize)> {
+        let mut rows = [0, 0];
+        let mut layout = Vec::new();
+        for action in Action::ALL {
+            let column = if action.group() == ActionGroup::Game { 0 } else { 1 };
+            // Leave a gap between the hotbar and the menu actions
+            if action == Action::MenuUp {
+                rows[column] += 1;
+            }
+            layout.push((action, column, rows[column]));
+            rows[column] += 1;
+        }
+        layout
+    }
+
+    fn conflict_message(bindings: &Bindings) -> Option<String> {
+        Action::ALL.iter().find_map(|action| {
+            let other = bindings.conflicts(*action).first().copied()?;
+            Some(format!("{} and {} both use {}", action.name(), other.name(), bindings.get(*action).label()))
+        })
+    }
+
+    fn update_controls(&mut self, bindings: &mut Bindings) -> Transition {
+        // Take the key before the Ui sees it, so Enter or Escape can be bound too
+        let mut captured = false;
+        if let Some(action) = self.rebinding {
+            if let Some(binding) = get_last_key_pressed().and_then(KeyBinding::capture) {
+                bindings.set(action, binding);
+                self.rebinding = None;
+                self.message = Self::conflict_message(bindings);
+                captured = true;
+            }
+        }
+
+        let layout = Self::layout();
+        let rows = layout.iter().map(|(_, _, row)| row + 1).max().unwrap_or(0);
+        let panel_width = COLUMN_WIDTH * 2.0 + 48.0;
+        let panel_height = ROW_HEIGHT + 16.0 + SPACING * (rows + 1) as f32 + 16.0;
+        let panel = Rect::new(
+            (screen_width() - panel_width) / 2.0,
+            (screen_height() - panel_height) / 2.0,
+            panel_width,
+            panel_height,
+        );
+        let top = panel.y + ROW_HEIGHT + 16.0;
+
+        self.ui.begin(bindings);
+        self.ui.panel(panel, "Controls");
+
+        for (action, column, row) in layout {
+            let x = panel.x + 16.0 + column as f32 * (COLUMN_WIDTH + 16.0);
+            let y = top + row as f32 * SPACING;
+            let color = if bindings.conflicts(action).is_empty() { WHITE } else { CONFLICT_COLOR };
+            self.ui.label(action.name(), x, y + 24.0, 28.0, color);
+
+            let label = if self.rebinding == Some(action) {
+                "Press a key...".to_string()
+            } else {
+                bindings.get(action).label()
+            };
+            let rect = Rect::new(x + LABEL_WIDTH, y, COLUMN_WIDTH - LABEL_WIDTH, BUTTON_HEIGHT);
+            if self.ui.button(action.id(), rect, &label) && !captured {
+                self.rebinding = if self.rebinding == Some(action) { None } else { Some(action) };
+            }
+        }
+
+        let buttons_y = top + rows as f32 * SPACING + 8.0;
+        let button_width = (panel.w - 48.0) / 2.0;
+        if self.ui.button("defaults", Rect::new(panel.x + 16.0, buttons_y, button_width, BUTTON_HEIGHT), "Defaults") && !captured {
+            *bindings = Bindings::default();
+            self.rebinding = None;
+            self.message = None;
+        }
+        let back = self.ui.button("back", Rect::new(panel.x + 32.0 + button_width, buttons_y, button_width, BUTTON_HEIGHT), "Back");
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        let back_pressed = self.rebinding.is_none() && self.ui.pressed(Action::MenuBack);
+        self.ui.end();
+
+        if (back || back_pressed) && !captured {
+            // Conflicting keys would make one of the actions unreachable
+            if let Some(conflict) = Self::conflict_message(bindings) {
+                self.message = Some(format!("{}. Change one of them first.", conflict));
+                return Transition::None;
+            }
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+}
+
+impl Screen for ControlsScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move { self.update_controls(&mut context.settings.bindings) })
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+            self.ui.draw();
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
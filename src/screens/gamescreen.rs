@@ -1,79 +1,222 @@
 use macroquad::prelude::*;
 
-use crate::{utils::{cells::Cells, directions::Directions, generatorcell::GeneratorCell, grid::Grid, helper::degrees2radians, movercell::MoverCell, pushcell::PushCell}, widgets::fpsviewer::FpsViewer};
+use rcmmm::{cellkind, levelcode, Cell, Directions, Grid};
+
+use crate::app::{
+    breakpoints::{Breakpoints, Condition},
+    controls::Action,
+    cycles::{Cycle, CycleDetector},
+    heatmap::Heatmap,
+    history::History,
+    settings::Settings,
+    stats::Stats,
+    timeline::Timeline,
+    trails::Trails,
+};
+use crate::widgets::{eventlog::EventLog, fpsviewer::FpsViewer, heatmapoverlay::HeatmapOverlay, hotbar::Hotbar, inspector::Inspector, statsviewer::StatsViewer, timelinebar::TimelineBar, trailrenderer::TrailRenderer};
+
+use super::{breakpointscreen::BreakpointScreen, pausescreen::PauseScreen, resizescreen::ResizeScreen, screen::{Screen, ScreenContext, ScreenFuture, ScreenResult, Transition}};
 
 pub struct GameScreen {
     grid: Grid,
     pan_velocity: (f32, f32),
-    pan_speed: f32,
     fps_viewer: FpsViewer,
     place_direction: Directions,
-    selected_cell: Cells,
+    hotbar: Hotbar,
+    tick: u32,
+    level_name: Option<String>,
+    has_unsaved_changes: bool,
+    history: History,
+    cycles: CycleDetector,
+    event_log: EventLog,
+    inspector: Inspector,
+    timeline: Timeline,
+    timeline_bar: TimelineBar,
+    // Tick of the timeline the grid is showing, earlier than the last one
+    // after stepping back
+    shown_tick: u64,
+    breakpoints: Breakpoints,
+    // The breakpoint that paused the game, until it plays again
+    break_message: Option<String>,
+    stats: Stats,
+    stats_viewer: StatsViewer,
+    // Result of the last statistics export
+    export_message: Option<String>,
+    heatmap: Heatmap,
+    heatmap_overlay: HeatmapOverlay,
+    trails: Trails,
+    trail_renderer: TrailRenderer,
 }
 
 impl GameScreen {
-    pub async fn new(w: u32, h: u32) -> GameScreen {
+    pub fn from_grid(grid: Grid, level_name: Option<String>) -> GameScreen {
+        let mut stats = Stats::new();
+        stats.record(&grid, 0);
+        let mut trails = Trails::new();
+        trails.record(&grid, 0);
         GameScreen {
-            grid: Grid::new(w, h).await,
+            cycles: CycleDetector::new(&grid),
+            heatmap: Heatmap::new(&grid),
+            timeline: Timeline::new(&grid),
+            grid,
             pan_velocity: (0.0, 0.0),
-            pan_speed: 7.5,
             fps_viewer: FpsViewer::new(20.0, 20.0, 60),
             place_direction: Directions::Right,
-            selected_cell: Cells::MoverCell(MoverCell::new(0.0, 0.0, Some(Directions::Right)).await),
+            hotbar: Hotbar::new(),
+            tick: 0,
+            level_name,
+            has_unsaved_changes: false,
+            history: History::new(),
+            event_log: EventLog::new(),
+            inspector: Inspector::new(),
+            timeline_bar: TimelineBar::new(),
+            shown_tick: 0,
+            breakpoints: Breakpoints::new(),
+            break_message: None,
+            stats,
+            stats_viewer: StatsViewer::new(20.0, 312.0),
+            export_message: None,
+            heatmap_overlay: HeatmapOverlay::new(),
+            trails,
+            trail_renderer: TrailRenderer::new(),
+        }
+    }
+
+    // Edits make the past ticks meaningless, so the timeline starts over
+    fn edited(&mut self) {
+        self.grid.ticks = 0;
+        self.cycles.reset(&self.grid);
+        self.timeline.reset(&self.grid);
+        self.shown_tick = 0;
+        self.breakpoints.rearm(&self.grid, 0);
+        self.stats.clear();
+        self.stats.record(&self.grid, 0);
+        self.heatmap.reset(&self.grid);
+        self.trails.clear();
+        self.trails.record(&self.grid, 0);
+        self.has_unsaved_changes = true;
+    }
+
+    fn show_tick(&mut self, tick: u64) {
+        if let Some((cells, events)) = self.timeline.state(tick) {
+            self.grid.cells = cells;
+            self.grid.events = events.to_vec();
+            self.grid.motions.clear();
+            self.grid.ticks = tick;
+            self.shown_tick = tick;
+            self.breakpoints.rearm(&self.grid, tick);
+            // The cells of another tick aren't the ones the trails followed
+            self.trails.clear();
+        }
+    }
+
+    // Playing on from an earlier tick drops the ticks that came after it
+    fn play_from_shown_tick(&mut self) {
+        if self.shown_tick < self.timeline.last_tick() {
+            self.timeline.truncate(self.shown_tick);
+            self.cycles.start_at(&self.grid, self.shown_tick);
+            self.stats.truncate(self.shown_tick);
+            self.heatmap.truncate(self.shown_tick);
         }
     }
 
-    pub async fn update(&mut self, tick: u32) {
+    fn stepped(&mut self, settings: &Settings) {
+        self.timeline.record(&self.grid);
+        self.shown_tick = self.timeline.last_tick();
+        let cycle = self.cycles.observe(&self.grid);
+        self.stats.record(&self.grid, self.shown_tick);
+        self.heatmap.record(&self.grid, self.shown_tick);
+        self.trails.record(&self.grid, self.shown_tick);
+        if settings.pause_when_stable && matches!(cycle, Cycle::Stable { .. }) {
+            self.grid.is_paused = true;
+        }
+        if let Some(condition) = self.breakpoints.check(&self.grid, self.shown_tick) {
+            self.grid.is_paused = true;
+            self.break_message = Some(format!("Break: {}", condition));
+        }
+    }
+
+    // Swaps in a grid from the undo history, keeping the view and play state
+    fn restore(&mut self, code: String) {
+        if let Ok(mut grid) = levelcode::decode(&code) {
+            grid.is_paused = self.grid.is_paused;
+            grid.set_draw_offset(self.grid.draw_offset.0, self.grid.draw_offset.1);
+            self.grid = grid;
+            self.edited();
+        }
+    }
+
+    // The grid square under a screen position, if there is one
+    fn square_at(&self, pos: (f32, f32)) -> Option<(u32, u32)> {
+        let x = ((pos.0 - self.grid.draw_offset.0 as f32) / 64.0).floor();
+        let y = ((pos.1 - self.grid.draw_offset.1 as f32) / 64.0).floor();
+        if x < 0.0 || y < 0.0 || x as usize >= self.grid.cells.len() || y as usize >= self.grid.cells[0].len() {
+            return None;
+        }
+        Some((x as u32, y as u32))
+    }
+
+    async fn update_game(&mut self, context: &mut ScreenContext) -> Transition {
         self.fps_viewer.update();
+        let bindings = &context.settings.bindings;
+
+        if bindings.pressed(Action::PauseMenu) {
+            return Transition::Push(Box::new(PauseScreen::new(
+                levelcode::encode(&self.grid),
+                self.level_name.clone(),
+                self.has_unsaved_changes,
+            )));
+        }
 
-        if is_key_down(KeyCode::D) {
-            self.pan_velocity.0 = -self.pan_speed;
+        let pan_speed = context.settings.pan_speed;
+        if bindings.down(Action::PanRight) {
+            self.pan_velocity.0 = -pan_speed;
         }
-        if is_key_down(KeyCode::A) {
-            self.pan_velocity.0 = self.pan_speed;
+        if bindings.down(Action::PanLeft) {
+            self.pan_velocity.0 = pan_speed;
         }
-        if is_key_down(KeyCode::S) {
-            self.pan_velocity.1 = -self.pan_speed;
+        if bindings.down(Action::PanDown) {
+            self.pan_velocity.1 = -pan_speed;
         }
-        if is_key_down(KeyCode::W) {
-            self.pan_velocity.1 = self.pan_speed;
+        if bindings.down(Action::PanUp) {
+            self.pan_velocity.1 = pan_speed;
+        }
+
+        if bindings.pressed(Action::RotateClockwise) {
+            self.place_direction = self.place_direction.clockwise();
         }
 
-        if is_key_pressed(KeyCode::E) {
-            self.place_direction = match self.place_direction {
-                Directions::Up => Directions::Right,
-                Directions::Right => Directions::Down,
-                Directions::Down => Directions::Left,
-                Directions::Left => Directions::Up,
-            };
+        if bindings.pressed(Action::RotateCounterClockwise) {
+            self.place_direction = self.place_direction.counter_clockwise();
         }
 
-        if is_key_pressed(KeyCode::Q) {
-            self.place_direction = match self.place_direction {
-                Directions::Up => Directions::Left,
-                Directions::Left => Directions::Down,
-                Directions::Down => Directions::Right,
-                Directions::Right => Directions::Up,
-            };
+        if bindings.pressed(Action::Redo) {
+            if let Some(code) = self.history.redo(levelcode::encode(&self.grid)) {
+                self.restore(code);
+            }
+        } else if bindings.pressed(Action::Undo) {
+            if let Some(code) = self.history.undo(levelcode::encode(&self.grid)) {
+                self.restore(code);
+            }
         }
 
-        if is_key_pressed(KeyCode::Z) {
-            self.selected_cell = match self.selected_cell {
-                Cells::MoverCell(_) => Cells::GeneratorCell(GeneratorCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-                Cells::PushCell(_) => Cells::MoverCell(MoverCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-                Cells::GeneratorCell(_) => Cells::PushCell(PushCell::new(0.0, 0.0, None).await),
-                _ => Cells::MoverCell(MoverCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-            };
+        if bindings.pressed(Action::PreviousCell) {
+            self.hotbar.select_previous();
         }
 
-        if is_key_pressed(KeyCode::X) {
-            self.selected_cell = match self.selected_cell {
-                Cells::MoverCell(_) => Cells::PushCell(PushCell::new(0.0, 0.0, None).await),
-                Cells::PushCell(_) => Cells::GeneratorCell(GeneratorCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-                Cells::GeneratorCell(_) => Cells::MoverCell(MoverCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-                _ => Cells::MoverCell(MoverCell::new(0.0, 0.0, Some(self.place_direction.clone())).await),
-            };
+        if bindings.pressed(Action::NextCell) {
+            self.hotbar.select_next();
         }
+
+        if bindings.pressed(Action::Resize) {
+            return Transition::Push(Box::new(ResizeScreen::new(
+                self.grid.cells.len() as u32,
+                self.grid.cells[0].len() as u32,
+                self.grid.occupied(),
+            )));
+        }
+
+        self.hotbar.update(bindings);
         
         self.grid.set_draw_offset(
             self.grid.draw_offset.0 + self.pan_velocity.0 as i32,
@@ -83,68 +226,205 @@ impl GameScreen {
         self.pan_velocity.0 *= 0.9;
         self.pan_velocity.1 *= 0.9;
 
-        if is_key_pressed(KeyCode::Space) {
+        if bindings.pressed(Action::ToggleEventLog) {
+            self.event_log.toggle();
+        }
+
+        if bindings.pressed(Action::ToggleInspector) {
+            self.inspector.toggle();
+        }
+
+        if bindings.pressed(Action::ToggleStats) {
+            self.stats_viewer.toggle();
+        }
+
+        if bindings.pressed(Action::ToggleHeatmap) {
+            self.heatmap_overlay.toggle();
+        }
+
+        if bindings.pressed(Action::ToggleTrails) {
+            self.trail_renderer.toggle();
+        }
+
+        if bindings.pressed(Action::ExportStats) {
+            let name = self.level_name.as_deref().unwrap_or("untitled");
+            self.export_message = Some(match self.stats.export(name) {
+                Ok(path) => format!("Statistics saved to {}", path.display()),
+                Err(err) => format!("Could not save statistics: {}", err),
+            });
+        }
+
+        if bindings.pressed(Action::Breakpoints) {
+            return Transition::Push(Box::new(BreakpointScreen::new(self.breakpoints.conditions(), None)));
+        }
+
+        if bindings.pressed(Action::TogglePause) {
             self.grid.is_paused = !self.grid.is_paused;
         }
+        if !self.grid.is_paused {
+            self.break_message = None;
+        }
+
+        if let Some(tick) = self.timeline_bar.update(self.timeline.first_tick(), self.timeline.last_tick()) {
+            self.grid.is_paused = true;
+            self.show_tick(tick);
+        }
+        if self.grid.is_paused && bindings.pressed(Action::StepBack) && self.shown_tick > self.timeline.first_tick() {
+            self.show_tick(self.shown_tick - 1);
+        }
+        if self.grid.is_paused && bindings.pressed(Action::StepForward) {
+            if self.shown_tick < self.timeline.last_tick() {
+                self.show_tick(self.shown_tick + 1);
+            } else {
+                self.grid.step();
+                self.stepped(&context.settings);
+            }
+        }
 
         let mouse_pos = mouse_position();
-        let coords_x = (((mouse_pos.0 - self.grid.draw_offset.0 as f32) / 64.0) as i32).max(0);
-        let coords_y = (((mouse_pos.1 - self.grid.draw_offset.1 as f32) / 64.0) as i32).max(0);
-        let dest_x = coords_x as f32 * 64.0 + self.grid.draw_offset.0 as f32;
-        let dest_y = coords_y as f32 * 64.0 + self.grid.draw_offset.1 as f32;
-        if is_mouse_button_down(MouseButton::Left) {
-            // self.grid.set_cell(coords_x as u32, coords_y as u32, Cells::MoverCell(MoverCell::new(dest_x, dest_y, Some(self.place_direction.clone())).await));
-            let moved_selected_cell = match self.selected_cell {
-                Cells::MoverCell(ref cell) => Cells::MoverCell(MoverCell::new(dest_x, dest_y, Some(self.place_direction.clone())).await),
-                Cells::PushCell(ref cell) => Cells::PushCell(PushCell::new(dest_x, dest_y, None).await),
-                Cells::GeneratorCell(ref cell) => Cells::GeneratorCell(GeneratorCell::new(dest_x, dest_y, Some(self.place_direction.clone())).await),
-                _ => Cells::MoverCell(MoverCell::new(dest_x, dest_y, Some(self.place_direction.clone())).await),
-            };
-            self.grid.set_cell(coords_x as u32, coords_y as u32, moved_selected_cell);
-        }
-
-        self.grid.update(tick).await;
+        let over_inspector = self.inspector.contains(&self.grid, mouse_pos);
+        let over_widget = self.hotbar.contains(mouse_pos)
+            || self.timeline_bar.contains(mouse_pos)
+            || self.event_log.contains(&self.grid.events, mouse_pos)
+            || self.stats_viewer.contains(mouse_pos)
+            || over_inspector;
+        let hovered = self.square_at(mouse_pos).filter(|_| !over_widget);
+        // Keep showing the square while the mouse is on its panel
+        if !over_inspector {
+            self.inspector.update(&mut self.grid, hovered);
+        }
+        if let Some((x, y)) = hovered {
+            // One undo step per brush stroke
+            if is_mouse_button_pressed(MouseButton::Left) {
+                self.history.record(levelcode::encode(&self.grid));
+            }
+            if is_mouse_button_pressed(MouseButton::Right) {
+                return Transition::Push(Box::new(BreakpointScreen::new(self.breakpoints.conditions(), hovered)));
+            }
+            // Holding the button over a square that already has the cell is not an edit
+            let placed_cell = Cell::new(self.hotbar.selected_cell().id, self.place_direction);
+            if is_mouse_button_down(MouseButton::Left)
+                && self.grid.cells[x as usize][y as usize] != placed_cell
+                && self.grid.set_cell(x, y, placed_cell).is_ok()
+            {
+                self.edited();
+            }
+        }
+
+        if !self.grid.is_paused {
+            self.has_unsaved_changes = true;
+            self.play_from_shown_tick();
+        }
+
+        self.grid.tick_interval = context.settings.tick_interval;
+        self.grid.interpolate = context.settings.smooth_movement;
+        if self.grid.update(self.tick) {
+            self.stepped(&context.settings);
+        }
+        self.tick += 1;
+
+        Transition::None
     }
 
-    pub async fn draw(&self, tick: u32) {
+    async fn draw_game(&self, context: &ScreenContext) {
         clear_background(BLACK);
         self.grid.draw();
-        self.fps_viewer.draw();
+        self.heatmap_overlay.draw(&self.grid, &self.heatmap);
+        self.trail_renderer.draw(&self.grid, &self.trails, self.shown_tick);
+        for condition in self.breakpoints.conditions() {
+            if let Condition::Occupied(x, y) = condition {
+                draw_rectangle_lines(
+                    x as f32 * 64.0 + self.grid.draw_offset.0 as f32,
+                    y as f32 * 64.0 + self.grid.draw_offset.1 as f32,
+                    64.0,
+                    64.0,
+                    4.0,
+                    RED,
+                );
+            }
+        }
+        if context.settings.show_fps {
+            self.fps_viewer.draw();
+        }
         draw_text(
-            &format!("Paused: {}, Tick: {}, Is Divisable by 10: {}", self.grid.is_paused, tick, tick % 10 == 0),
+            &format!(
+                "Paused: {}, Tick: {}, Is Divisable by {}: {}",
+                self.grid.is_paused,
+                self.tick,
+                self.grid.tick_interval,
+                self.tick.is_multiple_of(self.grid.tick_interval)
+            ),
             20.0,
             128.0,
             32.0,
             WHITE,
         );
+        draw_text(
+            &format!(
+                "Level: {}{}",
+                self.level_name.as_deref().unwrap_or("Untitled"),
+                if self.has_unsaved_changes { " (unsaved)" } else { "" }
+            ),
+            20.0,
+            160.0,
+            32.0,
+            WHITE,
+        );
+        draw_text(&self.cycles.cycle().to_string(), 20.0, 192.0, 32.0, WHITE);
+        if let Some(message) = &self.break_message {
+            draw_text(message, 20.0, 224.0, 32.0, RED);
+        }
+        if let Some(message) = &self.export_message {
+            draw_text(message, 20.0, 256.0, 32.0, WHITE);
+        }
+        if let Some(error) = cellkind::all().iter().find_map(|kind| kind.error()) {
+            draw_text(&format!("Script error in {}", error), 20.0, 288.0, 32.0, RED);
+        }
+        self.stats_viewer.draw(&self.stats);
+        self.event_log.draw(self.shown_tick, &self.grid.events);
+        self.inspector.draw(&self.grid);
+        self.timeline_bar.draw(self.timeline.first_tick(), self.timeline.last_tick(), self.shown_tick);
         
         // Draw the selected cell
         let mouse_pos = mouse_position();
-        let coords_x = (((mouse_pos.0 - self.grid.draw_offset.0 as f32) / 64.0) as i32).max(0);
-        let coords_y = (((mouse_pos.1 - self.grid.draw_offset.1 as f32) / 64.0) as i32).max(0);
-        let dest_x = coords_x as f32 * 64.0 + self.grid.draw_offset.0 as f32;
-        let dest_y = coords_y as f32 * 64.0 + self.grid.draw_offset.1 as f32;
-        let texture = match self.selected_cell {
-            Cells::MoverCell(ref cell) => &cell.texture,
-            Cells::PushCell(ref cell) => &cell.texture,
-            Cells::GeneratorCell(ref cell) => &cell.texture,
-            _ => &MoverCell::new(0.0, 0.0, Some(Directions::Right)).await.texture,
-        };
-        draw_texture_ex(
-            texture,
-            dest_x,
-            dest_y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(64.0, 64.0)),
-                rotation: match self.place_direction {
-                    Directions::Up => degrees2radians(270.0),
-                    Directions::Down => degrees2radians(90.0),
-                    Directions::Left => degrees2radians(180.0),
-                    Directions::Right => degrees2radians(0.0),
-                },
-                ..Default::default()
-            },
-        );
+        let coords_x = ((mouse_pos.0 - self.grid.draw_offset.0 as f32) / 64.0).floor();
+        let coords_y = ((mouse_pos.1 - self.grid.draw_offset.1 as f32) / 64.0).floor();
+        let dest_x = coords_x * 64.0 + self.grid.draw_offset.0 as f32;
+        let dest_y = coords_y * 64.0 + self.grid.draw_offset.1 as f32;
+        let selected_cell = Cell::new(self.hotbar.selected_cell().id, self.place_direction);
+        selected_cell.draw(dest_x, dest_y, selected_cell.rotation());
+
+        self.hotbar.draw(&self.place_direction, &context.settings.bindings);
+    }
+}
+
+impl Screen for GameScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(self.update_game(context))
+    }
+
+    fn draw<'a>(&'a self, context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(self.draw_game(context))
+    }
+
+    fn resume(&mut self, result: ScreenResult) -> ScreenFuture<'_, ()> {
+        Box::pin(async move {
+            match result {
+                ScreenResult::Saved(name) => {
+                    self.level_name = Some(name);
+                    self.has_unsaved_changes = false;
+                }
+                ScreenResult::Breakpoints(conditions) => {
+                    self.breakpoints.set(conditions, &self.grid, self.shown_tick);
+                }
+                ScreenResult::Resize(width, height, anchor) => {
+                    let code = levelcode::encode(&self.grid);
+                    if self.grid.resize(width, height, anchor).is_ok() {
+                        self.history.record(code);
+                        self.edited();
+                    }
+                }
+            }
+        })
     }
 }
@@ -0,0 +1,92 @@
+use macroquad::prelude::*;
+
+use rcmmm::levelcode;
+
+use crate::app::{controls::{Action, Bindings}, levels};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::{gamescreen::GameScreen, screen::{Screen, ScreenContext, ScreenFuture, Transition}};
+
+/// Lists the saved levels and opens the chosen one in a new game screen.
+pub struct LevelBrowserScreen {
+    ui: Ui,
+    levels: Vec<String>,
+    selected: Option<usize>,
+    message: Option<String>,
+}
+
+impl LevelBrowserScreen {
+    pub fn new() -> LevelBrowserScreen {
+        let levels = levels::list();
+        let mut ui = Ui::new();
+        ui.set_focus("levels");
+        LevelBrowserScreen {
+            ui,
+            selected: if levels.is_empty() { None } else { Some(0) },
+            levels,
+            message: None,
+        }
+    }
+
+    async fn open_selected(&mut self) -> Transition {
+        let Some(name) = self.selected.map(|i| self.levels[i].clone()) else {
+            return Transition::None;
+        };
+        let code = match levels::load(&name) {
+            Ok(code) => code,
+            Err(err) => {
+                self.message = Some(format!("Could not read {}: {}", name, err));
+                return Transition::None;
+            }
+        };
+        match levelcode::decode(&code) {
+            Ok(grid) => Transition::Reset(Box::new(GameScreen::from_grid(grid, Some(name)))),
+            Err(err) => {
+                self.message = Some(format!("Could not load {}: {}", name, err));
+                Transition::None
+            }
+        }
+    }
+
+    async fn update_browser(&mut self, bindings: &Bindings) -> Transition {
+        let panel = Rect::new(128.0, 96.0, 640.0, screen_height() - 192.0);
+        let list = Rect::new(panel.x + 16.0, panel.y + ROW_HEIGHT + 16.0, panel.w - 32.0, panel.h - ROW_HEIGHT - 96.0);
+        let buttons_y = list.y + list.h + 16.0;
+        let button_width = (list.w - 16.0) / 2.0;
+
+        self.ui.begin(bindings);
+        self.ui.panel(panel, "Load Level");
+        self.ui.list("levels", list, &self.levels, &mut self.selected);
+        if self.levels.is_empty() {
+            self.ui.label("No saved levels yet", list.x + 8.0, list.y + 28.0, 32.0, GRAY);
+        }
+        let load = self.ui.button("load", Rect::new(list.x, buttons_y, button_width, 40.0), "Load");
+        let back = self.ui.button("back", Rect::new(list.x + button_width + 16.0, buttons_y, button_width, 40.0), "Back");
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        let open_from_list = !back && !load && self.ui.pressed(Action::MenuConfirm) && self.ui.has_focus("levels");
+        self.ui.end();
+
+        if load || open_from_list {
+            return self.open_selected().await;
+        }
+        if back || self.ui.pressed(Action::MenuBack) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+}
+
+impl Screen for LevelBrowserScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(self.update_browser(&context.settings.bindings))
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            clear_background(BLACK);
+            self.ui.draw();
+        })
+    }
+}
@@ -1,2 +1,11 @@
+pub mod screen;
+pub mod screenmanager;
 pub mod titlescreen;
 pub mod gamescreen;
+pub mod pausescreen;
+pub mod levelbrowserscreen;
+pub mod newlevelscreen;
+pub mod resizescreen;
+pub mod breakpointscreen;
+pub mod settingsscreen;
+pub mod controlsscreen;
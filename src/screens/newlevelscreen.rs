@@ -0,0 +1,142 @@
+use macroquad::prelude::*;
+
+use rcmmm::{Grid, MAX_SIZE};
+
+use crate::app::{controls::{Action, Bindings}, levels, templates::Template};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::{gamescreen::GameScreen, screen::{Screen, ScreenContext, ScreenFuture, Transition}};
+
+const PANEL_WIDTH: f32 = 560.0;
+const LABEL_WIDTH: f32 = 160.0;
+const SPACING: f32 = 48.0;
+
+/// Asks for the size, wrap mode, name and starting template of a new level
+/// before opening it in the game.
+pub struct NewLevelScreen {
+    ui: Ui,
+    width: String,
+    height: String,
+    name: String,
+    wrap: bool,
+    template_names: Vec<String>,
+    template: Option<usize>,
+    message: Option<String>,
+}
+
+impl NewLevelScreen {
+    pub fn new() -> NewLevelScreen {
+        let mut ui = Ui::new();
+        ui.set_focus("width");
+        NewLevelScreen {
+            ui,
+            width: "50".to_string(),
+            height: "50".to_string(),
+            name: String::new(),
+            wrap: false,
+            template_names: Template::ALL.iter().map(|t| t.name().to_string()).collect(),
+            template: Some(0),
+            message: None,
+        }
+    }
+
+    fn parse_size(label: &str, value: &str, min: u32) -> Result<u32, String> {
+        let size: u32 = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} must be a whole number", label))?;
+        if size < min || size > MAX_SIZE {
+            return Err(format!("{} must be between {} and {}", label, min, MAX_SIZE));
+        }
+        Ok(size)
+    }
+
+    async fn create(&mut self) -> Transition {
+        let template = Template::ALL[self.template.unwrap_or(0)];
+        let size = Self::parse_size("Width", &self.width, template.min_size())
+            .and_then(|w| Ok((w, Self::parse_size("Height", &self.height, template.min_size())?)));
+        let (width, height) = match size {
+            Ok(size) => size,
+            Err(err) => {
+                self.message = Some(err);
+                return Transition::None;
+            }
+        };
+
+        let name = self.name.trim();
+        if !name.is_empty() && !levels::is_valid_name(name) {
+            self.message = Some("Names may only use letters, digits, spaces, - and _".to_string());
+            return Transition::None;
+        }
+        if levels::list().iter().any(|level| level == name) {
+            self.message = Some(format!("A level called {} already exists", name));
+            return Transition::None;
+        }
+
+        let grid = Grid::new(width, height).and_then(|mut grid| {
+            grid.wrap = self.wrap;
+            template.apply(&mut grid)?;
+            Ok(grid)
+        });
+        let grid = match grid {
+            Ok(grid) => grid,
+            Err(err) => {
+                self.message = Some(err);
+                return Transition::None;
+            }
+        };
+        let name = if name.is_empty() { None } else { Some(name.to_string()) };
+        Transition::Reset(Box::new(GameScreen::from_grid(grid, name)))
+    }
+
+    async fn update_form(&mut self, bindings: &Bindings) -> Transition {
+        let panel = Rect::new(128.0, 96.0, PANEL_WIDTH, ROW_HEIGHT + 16.0 + SPACING * 8.0);
+        let row = |i: usize| panel.y + ROW_HEIGHT + 16.0 + i as f32 * SPACING;
+        let field = |i: usize| Rect::new(panel.x + LABEL_WIDTH, row(i), panel.w - LABEL_WIDTH - 16.0, 40.0);
+
+        self.ui.begin(bindings);
+        self.ui.panel(panel, "New Level");
+
+        self.ui.label("Name", panel.x + 16.0, row(0) + 28.0, 32.0, WHITE);
+        self.ui.text_input("name", field(0), &mut self.name, 32);
+        self.ui.label("Width", panel.x + 16.0, row(1) + 28.0, 32.0, WHITE);
+        self.ui.text_input("width", field(1), &mut self.width, 3);
+        self.ui.label("Height", panel.x + 16.0, row(2) + 28.0, 32.0, WHITE);
+        self.ui.text_input("height", field(2), &mut self.height, 3);
+        self.ui.toggle("wrap", field(3), "Wrap around edges", &mut self.wrap);
+        self.ui.label("Template", panel.x + 16.0, row(4) + 28.0, 32.0, WHITE);
+        let templates = Rect::new(field(4).x, field(4).y, field(4).w, ROW_HEIGHT * 3.0);
+        self.ui.list("template", templates, &self.template_names, &mut self.template);
+
+        let button_width = (panel.w - 48.0) / 2.0;
+        let create = self.ui.button("create", Rect::new(panel.x + 16.0, row(7), button_width, 40.0), "Create");
+        let back = self.ui.button("back", Rect::new(panel.x + 32.0 + button_width, row(7), button_width, 40.0), "Back");
+        let submitted = self.ui.wants_keyboard() && self.ui.pressed(Action::MenuConfirm);
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        self.ui.end();
+
+        if create || submitted {
+            return self.create().await;
+        }
+        if back || self.ui.pressed(Action::MenuBack) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+}
+
+impl Screen for NewLevelScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(self.update_form(&context.settings.bindings))
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            clear_background(BLACK);
+            self.ui.draw();
+        })
+    }
+}
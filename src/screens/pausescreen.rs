@@ -0,0 +1,232 @@
+use macroquad::prelude::*;
+
+use crate::app::{controls::{Action, Bindings}, levels};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::{
+    levelbrowserscreen::LevelBrowserScreen,
+    settingsscreen::SettingsScreen,
+    screen::{Screen, ScreenContext, ScreenFuture, ScreenResult, Transition},
+    titlescreen::TitleScreen,
+};
+
+const PANEL_WIDTH: f32 = 420.0;
+const BUTTON_HEIGHT: f32 = 40.0;
+const SPACING: f32 = 48.0;
+
+enum PauseMode {
+    Menu,
+    SaveAs,
+    ConfirmQuit,
+    ConfirmLoad,
+}
+
+/// Overlay opened with Escape in the game. The game screen underneath is not
+/// updated while this is open, so the level code taken when pausing stays
+/// current until the menu is closed.
+pub struct PauseScreen {
+    ui: Ui,
+    mode: PauseMode,
+    level_code: String,
+    level_name: Option<String>,
+    has_unsaved_changes: bool,
+    saved: bool,
+    save_as_name: String,
+    message: Option<String>,
+}
+
+impl PauseScreen {
+    pub fn new(level_code: String, level_name: Option<String>, has_unsaved_changes: bool) -> PauseScreen {
+        let mut ui = Ui::new();
+        ui.set_focus("resume");
+        PauseScreen {
+            ui,
+            mode: PauseMode::Menu,
+            level_code,
+            level_name,
+            has_unsaved_changes,
+            saved: false,
+            save_as_name: String::new(),
+            message: None,
+        }
+    }
+
+    fn panel(&mut self, title: &str, rows: usize) -> Rect {
+        let height = ROW_HEIGHT + 16.0 + rows as f32 * SPACING;
+        let rect = Rect::new(
+            (screen_width() - PANEL_WIDTH) / 2.0,
+            (screen_height() - height) / 2.0,
+            PANEL_WIDTH,
+            height,
+        );
+        self.ui.panel(rect, title);
+        rect
+    }
+
+    fn row(panel: Rect, row: usize) -> Rect {
+        Rect::new(
+            panel.x + 16.0,
+            panel.y + ROW_HEIGHT + 16.0 + row as f32 * SPACING,
+            panel.w - 32.0,
+            BUTTON_HEIGHT,
+        )
+    }
+
+    fn save(&mut self, name: String) {
+        match levels::save(&name, &self.level_code) {
+            Ok(()) => {
+                self.message = Some(format!("Saved as {}", name));
+                self.level_name = Some(name);
+                self.has_unsaved_changes = false;
+                self.saved = true;
+            }
+            Err(err) => self.message = Some(format!("Could not save: {}", err)),
+        }
+    }
+
+    fn open_save_as(&mut self) {
+        self.save_as_name = self.level_name.clone().unwrap_or_default();
+        self.mode = PauseMode::SaveAs;
+        self.ui.set_focus("save_as_name");
+    }
+
+    fn back_to_menu(&mut self) {
+        self.mode = PauseMode::Menu;
+        self.ui.set_focus("resume");
+    }
+
+    fn resume_game(&self) -> Transition {
+        match (&self.level_name, self.saved) {
+            (Some(name), true) => Transition::PopWith(ScreenResult::Saved(name.clone())),
+            _ => Transition::Pop,
+        }
+    }
+
+    fn update_menu(&mut self) -> Transition {
+        let panel = self.panel("Paused", 6);
+
+        if self.ui.button("resume", Self::row(panel, 0), "Resume") {
+            return self.resume_game();
+        }
+        if self.ui.button("save", Self::row(panel, 1), "Save") {
+            match self.level_name.clone() {
+                Some(name) => self.save(name),
+                None => self.open_save_as(),
+            }
+        }
+        if self.ui.button("save_as", Self::row(panel, 2), "Save As") {
+            self.open_save_as();
+        }
+        if self.ui.button("load", Self::row(panel, 3), "Load") {
+            if self.has_unsaved_changes {
+                self.mode = PauseMode::ConfirmLoad;
+            } else {
+                return Transition::Push(Box::new(LevelBrowserScreen::new()));
+            }
+        }
+        if self.ui.button("settings", Self::row(panel, 4), "Settings") {
+            return Transition::Push(Box::new(SettingsScreen::new()));
+        }
+        if self.ui.button("quit", Self::row(panel, 5), "Quit to Title") {
+            if self.has_unsaved_changes {
+                self.mode = PauseMode::ConfirmQuit;
+            } else {
+                return Transition::Reset(Box::new(TitleScreen::new()));
+            }
+        }
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+
+        if self.ui.pressed(Action::MenuBack) {
+            return self.resume_game();
+        }
+        Transition::None
+    }
+
+    fn update_save_as(&mut self) {
+        let panel = self.panel("Save As", 3);
+
+        self.ui.text_input("save_as_name", Self::row(panel, 0), &mut self.save_as_name, 32);
+        let confirmed = self.ui.button("save_as_confirm", Self::row(panel, 1), "Save");
+        if confirmed || (self.ui.wants_keyboard() && self.ui.pressed(Action::MenuConfirm)) {
+            let name = self.save_as_name.trim().to_string();
+            if !levels::is_valid_name(&name) {
+                self.message = Some("Names may only use letters, digits, spaces, - and _".to_string());
+            } else if levels::list().contains(&name) {
+                self.message = Some(format!("A level called {} already exists", name));
+            } else {
+                self.save(name);
+                self.back_to_menu();
+            }
+        }
+        if self.ui.button("save_as_cancel", Self::row(panel, 2), "Cancel") || self.ui.pressed(Action::MenuBack) {
+            self.back_to_menu();
+        }
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+    }
+
+    /// Asks before throwing away unsaved changes. Returns true once the user
+    /// agreed to discard them.
+    fn update_confirm(&mut self, action: &str) -> bool {
+        let panel = self.panel("Unsaved changes", 3);
+
+        self.ui.label("Your changes will be lost.", panel.x + 16.0, Self::row(panel, 0).y + 28.0, 32.0, WHITE);
+        if self.ui.button("confirm_discard", Self::row(panel, 1), &format!("Discard and {}", action)) {
+            return true;
+        }
+        if self.ui.button("confirm_cancel", Self::row(panel, 2), "Cancel") || self.ui.pressed(Action::MenuBack) {
+            self.back_to_menu();
+        }
+        false
+    }
+
+    fn update_pause(&mut self, bindings: &Bindings) -> Transition {
+        self.ui.begin(bindings);
+        let transition = match self.mode {
+            PauseMode::Menu => self.update_menu(),
+            PauseMode::SaveAs => {
+                self.update_save_as();
+                Transition::None
+            }
+            PauseMode::ConfirmQuit => {
+                if self.update_confirm("Quit") {
+                    Transition::Reset(Box::new(TitleScreen::new()))
+                } else {
+                    Transition::None
+                }
+            }
+            PauseMode::ConfirmLoad => {
+                if self.update_confirm("Load") {
+                    // The changes are given up already, so there is no menu to go back to
+                    Transition::Replace(Box::new(LevelBrowserScreen::new()))
+                } else {
+                    Transition::None
+                }
+            }
+        };
+        self.ui.end();
+        transition
+    }
+}
+
+impl Screen for PauseScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move { self.update_pause(&context.settings.bindings) })
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+            self.ui.draw();
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,151 @@
+use macroquad::prelude::*;
+
+use rcmmm::{Anchor, MAX_SIZE};
+
+use crate::app::controls::{Action, Bindings};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::screen::{Screen, ScreenContext, ScreenFuture, ScreenResult, Transition};
+
+const PANEL_WIDTH: f32 = 480.0;
+const LABEL_WIDTH: f32 = 140.0;
+const SPACING: f32 = 48.0;
+const ANCHOR_SIZE: f32 = 40.0;
+
+/// Overlay for changing the size of the grid in the game screen. The resize
+/// itself happens in the game screen so it can be undone.
+pub struct ResizeScreen {
+    ui: Ui,
+    old_width: u32,
+    old_height: u32,
+    occupied: Vec<(u32, u32)>,
+    width: String,
+    height: String,
+    anchor: Anchor,
+    // Number of cells the user agreed to delete, so changing the size again asks again
+    confirmed_loss: Option<usize>,
+    message: Option<String>,
+}
+
+impl ResizeScreen {
+    pub fn new(old_width: u32, old_height: u32, occupied: Vec<(u32, u32)>) -> ResizeScreen {
+        let mut ui = Ui::new();
+        ui.set_focus("width");
+        ResizeScreen {
+            ui,
+            old_width,
+            old_height,
+            occupied,
+            width: old_width.to_string(),
+            height: old_height.to_string(),
+            anchor: Anchor::TopLeft,
+            confirmed_loss: None,
+            message: None,
+        }
+    }
+
+    fn parse_size(label: &str, value: &str) -> Result<u32, String> {
+        let size: u32 = value
+            .trim()
+            .parse()
+            .map_err(|_| format!("{} must be a whole number", label))?;
+        if size == 0 || size > MAX_SIZE {
+            return Err(format!("{} must be between 1 and {}", label, MAX_SIZE));
+        }
+        Ok(size)
+    }
+
+    fn apply(&mut self) -> Transition {
+        let size = Self::parse_size("Width", &self.width).and_then(|w| Ok((w, Self::parse_size("Height", &self.height)?)));
+        let (width, height) = match size {
+            Ok(size) => size,
+            Err(err) => {
+                self.message = Some(err);
+                return Transition::None;
+            }
+        };
+
+        let lost = self.anchor.cells_lost(&self.occupied, self.old_width, self.old_height, width, height);
+        if lost > 0 && self.confirmed_loss != Some(lost) {
+            self.confirmed_loss = Some(lost);
+            self.message = Some(format!("This deletes {} cells. Apply again to confirm.", lost));
+            return Transition::None;
+        }
+        Transition::PopWith(ScreenResult::Resize(width, height, self.anchor))
+    }
+
+    fn update_form(&mut self, bindings: &Bindings) -> Transition {
+        let panel_height = ROW_HEIGHT + 16.0 + SPACING * 3.0 + ANCHOR_SIZE * 3.0 + 16.0;
+        let panel = Rect::new(
+            (screen_width() - PANEL_WIDTH) / 2.0,
+            (screen_height() - panel_height) / 2.0,
+            PANEL_WIDTH,
+            panel_height,
+        );
+        let row = |i: usize| panel.y + ROW_HEIGHT + 16.0 + i as f32 * SPACING;
+        let field = |i: usize| Rect::new(panel.x + LABEL_WIDTH, row(i), panel.w - LABEL_WIDTH - 16.0, 40.0);
+
+        self.ui.begin(bindings);
+        self.ui.panel(panel, &format!("Resize ({}x{})", self.old_width, self.old_height));
+
+        self.ui.label("Width", panel.x + 16.0, row(0) + 28.0, 32.0, WHITE);
+        if self.ui.text_input("width", field(0), &mut self.width, 3) {
+            self.confirmed_loss = None;
+        }
+        self.ui.label("Height", panel.x + 16.0, row(1) + 28.0, 32.0, WHITE);
+        if self.ui.text_input("height", field(1), &mut self.height, 3) {
+            self.confirmed_loss = None;
+        }
+
+        self.ui.label("Anchor", panel.x + 16.0, row(2) + 28.0, 32.0, WHITE);
+        for (i, anchor) in Anchor::ALL.iter().enumerate() {
+            let rect = Rect::new(
+                panel.x + LABEL_WIDTH + (i % 3) as f32 * (ANCHOR_SIZE + 4.0),
+                row(2) + (i / 3) as f32 * (ANCHOR_SIZE + 4.0),
+                ANCHOR_SIZE,
+                ANCHOR_SIZE,
+            );
+            let label = if *anchor == self.anchor { "#" } else { "" };
+            if self.ui.button(&format!("anchor{}", i), rect, label) {
+                self.anchor = *anchor;
+                self.confirmed_loss = None;
+            }
+        }
+
+        let buttons_y = row(2) + ANCHOR_SIZE * 3.0 + 24.0;
+        let button_width = (panel.w - 48.0) / 2.0;
+        let apply = self.ui.button("apply", Rect::new(panel.x + 16.0, buttons_y, button_width, 40.0), "Apply");
+        let cancel = self.ui.button("cancel", Rect::new(panel.x + 32.0 + button_width, buttons_y, button_width, 40.0), "Cancel");
+        let submitted = self.ui.wants_keyboard() && self.ui.pressed(Action::MenuConfirm);
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        self.ui.end();
+
+        if apply || submitted {
+            return self.apply();
+        }
+        if cancel || self.ui.pressed(Action::MenuBack) {
+            return Transition::Pop;
+        }
+        Transition::None
+    }
+}
+
+impl Screen for ResizeScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move { self.update_form(&context.settings.bindings) })
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+            self.ui.draw();
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
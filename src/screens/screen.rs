@@ -0,0 +1,59 @@
+use std::{future::Future, pin::Pin};
+
+use rcmmm::Anchor;
+
+use crate::app::{breakpoints::Condition, settings::Settings};
+
+/// Future returned by `Screen` methods. Screens are stored as trait objects, so
+/// the futures have to be boxed. The methods stay async so a screen can await
+/// macroquad's loaders, which are asynchronous on the web.
+pub type ScreenFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// State shared by all screens, owned by the screen manager.
+pub struct ScreenContext {
+    pub settings: Settings,
+}
+
+/// Passed to the screen below when an overlay closes with `Transition::PopWith`.
+pub enum ScreenResult {
+    /// The level was saved under this name.
+    Saved(String),
+    /// The grid should be resized to this width and height.
+    Resize(u32, u32, Anchor),
+    /// The game screen should break on these conditions from now on.
+    Breakpoints(Vec<Condition>),
+}
+
+/// What the screen manager should do after a screen's update.
+pub enum Transition {
+    None,
+    /// Open a screen on top of the current one.
+    Push(Box<dyn Screen>),
+    /// Close the current screen and go back to the one below it.
+    Pop,
+    /// Like `Pop`, but hands a result to the screen below.
+    PopWith(ScreenResult),
+    /// Swap the current screen for another one.
+    Replace(Box<dyn Screen>),
+    /// Close every screen and open this one, e.g. when quitting to the title.
+    Reset(Box<dyn Screen>),
+    /// Close the application.
+    Exit,
+}
+
+pub trait Screen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition>;
+
+    fn draw<'a>(&'a self, context: &'a ScreenContext) -> ScreenFuture<'a, ()>;
+
+    /// Called when the screen is back on top after an overlay closed with a result.
+    fn resume(&mut self, _result: ScreenResult) -> ScreenFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    /// Overlays are drawn on top of the screen below them instead of
+    /// replacing it, e.g. the pause menu over the game.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,64 @@
+use super::screen::{Screen, ScreenContext, Transition};
+
+/// Stack of open screens. Only the top screen is updated; it is drawn along
+/// with any screens it overlays.
+pub struct ScreenManager {
+    stack: Vec<Box<dyn Screen>>,
+    context: ScreenContext,
+}
+
+impl ScreenManager {
+    pub fn new(root: Box<dyn Screen>, context: ScreenContext) -> ScreenManager {
+        ScreenManager {
+            stack: vec![root],
+            context,
+        }
+    }
+
+    /// True once the last screen is closed and the application should exit.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    pub async fn update(&mut self) {
+        let transition = match self.stack.last_mut() {
+            Some(screen) => screen.update(&mut self.context).await,
+            None => return,
+        };
+
+        match transition {
+            Transition::None => {}
+            Transition::Push(screen) => self.stack.push(screen),
+            Transition::Pop => {
+                self.stack.pop();
+            }
+            Transition::PopWith(result) => {
+                self.stack.pop();
+                if let Some(screen) = self.stack.last_mut() {
+                    screen.resume(result).await;
+                }
+            }
+            Transition::Replace(screen) => {
+                self.stack.pop();
+                self.stack.push(screen);
+            }
+            Transition::Reset(screen) => {
+                self.stack.clear();
+                self.stack.push(screen);
+            }
+            Transition::Exit => self.stack.clear(),
+        }
+    }
+
+    pub async fn draw(&self) {
+        // Start from the topmost screen that is not an overlay
+        let mut first = self.stack.len().saturating_sub(1);
+        while first > 0 && self.stack[first].is_overlay() {
+            first -= 1;
+        }
+
+        for screen in &self.stack[first..] {
+            screen.draw(&self.context).await;
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use macroquad::prelude::*;
+
+use crate::app::{controls::Action, settings::{Settings, WINDOW_HEIGHTS, WINDOW_WIDTHS}};
+use crate::widgets::ui::{Ui, ROW_HEIGHT};
+
+use super::{controlsscreen::ControlsScreen, screen::{Screen, ScreenContext, ScreenFuture, Transition}};
+
+const PANEL_WIDTH: f32 = 560.0;
+const SPACING: f32 = 48.0;
+
+/// Overlay for editing the settings. Changes apply immediately and are written
+/// to the settings file when the screen is closed.
+pub struct SettingsScreen {
+    ui: Ui,
+    message: Option<String>,
+    // The window size changed but the window hasn't been resized yet
+    resize_pending: bool,
+}
+
+impl SettingsScreen {
+    pub fn new() -> SettingsScreen {
+        let mut ui = Ui::new();
+        ui.set_focus("pan_speed");
+        SettingsScreen { ui, message: None, resize_pending: false }
+    }
+
+    fn update_form(&mut self, settings: &mut Settings) -> Transition {
+        let panel_height = ROW_HEIGHT + 16.0 + SPACING * 9.0;
+        let panel = Rect::new(
+            (screen_width() - PANEL_WIDTH) / 2.0,
+            (screen_height() - panel_height) / 2.0,
+            PANEL_WIDTH,
+            panel_height,
+        );
+        let row = |i: usize| Rect::new(panel.x + 16.0, panel.y + ROW_HEIGHT + 16.0 + i as f32 * SPACING, panel.w - 32.0, 40.0);
+
+        self.ui.begin(&settings.bindings);
+        self.ui.panel(panel, "Settings");
+
+        self.ui.slider("pan_speed", row(0), "Pan speed", &mut settings.pan_speed, 1.0, 30.0);
+
+        let mut tick_interval = settings.tick_interval as f32;
+        if self.ui.slider("tick_interval", row(1), "Frames per tick", &mut tick_interval, 1.0, 60.0) {
+            settings.tick_interval = tick_interval.round() as u32;
+        }
+
+        let mut window_width = settings.window_width as f32;
+        let mut window_height = settings.window_height as f32;
+        let (min_width, max_width) = (*WINDOW_WIDTHS.start() as f32, *WINDOW_WIDTHS.end() as f32);
+        let (min_height, max_height) = (*WINDOW_HEIGHTS.start() as f32, *WINDOW_HEIGHTS.end() as f32);
+        let width_changed = self.ui.slider("window_width", row(2), "Window width", &mut window_width, min_width, max_width);
+        let height_changed = self.ui.slider("window_height", row(3), "Window height", &mut window_height, min_height, max_height);
+        if width_changed || height_changed {
+            settings.window_width = window_width.round() as i32;
+            settings.window_height = window_height.round() as i32;
+            self.resize_pending = true;
+        }
+        // Resizing while a slider is dragged would move the slider under the mouse
+        if self.resize_pending && !is_mouse_button_down(MouseButton::Left) {
+            request_new_screen_size(settings.window_width as f32, settings.window_height as f32);
+            self.resize_pending = false;
+        }
+
+        self.ui.toggle("show_fps", row(4), "Show FPS", &mut settings.show_fps);
+        self.ui.toggle("smooth_movement", row(5), "Smooth movement", &mut settings.smooth_movement);
+        self.ui.toggle("pause_when_stable", row(6), "Pause when stable", &mut settings.pause_when_stable);
+        if self.ui.button("controls", row(7), "Controls") {
+            self.ui.end();
+            return Transition::Push(Box::new(ControlsScreen::new()));
+        }
+
+        let button_width = (panel.w - 48.0) / 2.0;
+        let last_row = row(8);
+        if self.ui.button("defaults", Rect::new(last_row.x, last_row.y, button_width, 40.0), "Defaults") {
+            *settings = Settings::default();
+            self.resize_pending = true;
+        }
+        let back = self.ui.button("back", Rect::new(last_row.x + button_width + 16.0, last_row.y, button_width, 40.0), "Back");
+
+        if let Some(message) = &self.message {
+            self.ui.label(message, panel.x, panel.y + panel.h + 32.0, 32.0, WHITE);
+        }
+        self.ui.end();
+
+        if back || self.ui.pressed(Action::MenuBack) {
+            if self.resize_pending {
+                request_new_screen_size(settings.window_width as f32, settings.window_height as f32);
+                self.resize_pending = false;
+            }
+            return match settings.save() {
+                Ok(()) => Transition::Pop,
+                // Let the user leave anyway once they have seen the error
+                Err(_) if self.message.is_some() => Transition::Pop,
+                Err(err) => {
+                    self.message = Some(format!("Could not save settings: {}. Press Back again to close.", err));
+                    Transition::None
+                }
+            };
+        }
+        Transition::None
+    }
+}
+
+impl Screen for SettingsScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move { self.update_form(&mut context.settings) })
+    }
+
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.5));
+            self.ui.draw();
+        })
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -1,49 +1,68 @@
 use macroquad::prelude::*;
 
+use crate::app::controls::Bindings;
+use crate::widgets::ui::Ui;
+
+use super::{levelbrowserscreen::LevelBrowserScreen, newlevelscreen::NewLevelScreen, settingsscreen::SettingsScreen, screen::{Screen, ScreenContext, ScreenFuture, Transition}};
+
 pub struct TitleScreen {
-    selected: i32,
+    ui: Ui,
     options: Vec<String>,
     options_translations: Vec<String>,
 }
 
 impl TitleScreen {
     pub fn new() -> TitleScreen {
+        let options = vec!["new".to_string(), "load".to_string(), "settings".to_string(), "exit".to_string()];
+        let mut ui = Ui::new();
+        ui.set_focus(&options[0]);
         TitleScreen {
-            selected: 0,
-            options: vec!["new".to_string(), "load".to_string(), "exit".to_string()],
-            options_translations: vec!["New Game".to_string(), "Load Game".to_string(), "Exit".to_string()],
+            ui,
+            options,
+            options_translations: vec![
+                "New Game".to_string(),
+                "Load Game".to_string(),
+                "Settings".to_string(),
+                "Exit".to_string(),
+            ],
         }
     }
 
-    pub fn update(&mut self) -> Option<String> {
-        if is_key_pressed(KeyCode::Down) {
-            self.selected += 1;
-            if self.selected >= self.options.len() as i32 {
-                self.selected = 0;
-            }
-        }
-        if is_key_pressed(KeyCode::Up) {
-            self.selected -= 1;
-            if self.selected < 0 {
-                self.selected = self.options.len() as i32 - 1;
+    fn update_menu(&mut self, bindings: &Bindings) -> Option<String> {
+        let mut selected = None;
+
+        self.ui.begin(bindings);
+        self.ui.label("cell_machine_mystic_mod.rs", 128.0, 128.0, 64.0, YELLOW);
+        self.ui.label("Alpha v0.1.4 Suuuuper Buggy!", 128.0, 192.0, 32.0, GRAY);
+        for (i, option) in self.options.iter().enumerate() {
+            let rect = Rect::new(128.0, 232.0 + 48.0 * i as f32, 320.0, 40.0);
+            if self.ui.button(option, rect, &self.options_translations[i]) {
+                selected = Some(option.clone());
             }
         }
-        if is_key_pressed(KeyCode::Enter) {
-            return Some(self.options[self.selected as usize].clone());
-        }
+        self.ui.end();
 
-        None
+        selected
     }
+}
 
-    pub fn draw(&self) {
-        clear_background(BLACK);
+impl Screen for TitleScreen {
+    fn update<'a>(&'a mut self, context: &'a mut ScreenContext) -> ScreenFuture<'a, Transition> {
+        Box::pin(async move {
+            match self.update_menu(&context.settings.bindings).as_deref() {
+                Some("new") => Transition::Push(Box::new(NewLevelScreen::new())),
+                Some("load") => Transition::Push(Box::new(LevelBrowserScreen::new())),
+                Some("settings") => Transition::Push(Box::new(SettingsScreen::new())),
+                Some("exit") => Transition::Exit,
+                _ => Transition::None,
+            }
+        })
+    }
 
-        draw_text("cell_machine_mystic_mod.rs", 128.0, 128.0, 64.0, YELLOW);
-        draw_text("Alpha v0.1.4 Suuuuper Buggy!", 128.0, 192.0, 32.0, GRAY);
-        for (i, _option) in self.options.iter().enumerate() {
-            let color = if i as i32 == self.selected { WHITE } else { GRAY };
-            let text = if i as i32 == self.selected { format!("> {}", self.options_translations[i]) } else { self.options_translations[i].clone() };
-            draw_text(text.as_str(), 128.0, 256.0 + 40.0 * i as f32, 32.0, color);
-        }
+    fn draw<'a>(&'a self, _context: &'a ScreenContext) -> ScreenFuture<'a, ()> {
+        Box::pin(async move {
+            clear_background(BLACK);
+            self.ui.draw();
+        })
     }
 }
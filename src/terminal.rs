@@ -0,0 +1,178 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use rcmmm::{Cell, Directions, Grid};
+
+use crate::app::settings::Settings;
+
+// Plays a grid in the terminal, for machines without a display. Keys:
+//
+//     space          play / pause
+//     n              step once
+//     arrows, wasd   pan
+//     + -            faster / slower
+//     q, escape      quit
+//
+// The grid steps through `Grid::update` at 60 frames per second, the same way
+// the game screen drives it. Every square is two characters wide so the grid
+// comes out roughly square.
+const FRAME: Duration = Duration::from_micros(16_667);
+const MAX_TICK_INTERVAL: u32 = 120;
+
+struct Viewer {
+    grid: Grid,
+    frame: u32,
+    // Top left square in view
+    offset: (i32, i32),
+    ascii: bool,
+    quit: bool,
+}
+
+fn arrow(direction: Directions, ascii: bool) -> char {
+    match (direction, ascii) {
+        (Directions::Right, false) => '→',
+        (Directions::Down, false) => '↓',
+        (Directions::Left, false) => '←',
+        (Directions::Up, false) => '↑',
+        (Directions::Right, true) => '>',
+        (Directions::Down, true) => 'v',
+        (Directions::Left, true) => '<',
+        (Directions::Up, true) => '^',
+    }
+}
+
+fn glyph(cell: Cell, ascii: bool) -> (String, Color) {
+    let arrow = arrow(cell.direction, ascii);
+    match (cell.id, ascii) {
+        (0, false) => ("· ".to_string(), Color::DarkGrey),
+        (0, true) => (". ".to_string(), Color::DarkGrey),
+        (1, _) => (format!("{} ", arrow), Color::Cyan),
+        (2, false) => ("■ ".to_string(), Color::Yellow),
+        (2, true) => ("# ".to_string(), Color::Yellow),
+        (3, _) => (format!("G{}", arrow), Color::Green),
+        (4, false) => ("██".to_string(), Color::Grey),
+        (4, true) => ("XX".to_string(), Color::Grey),
+        _ => {
+            let kind = cell.kind();
+            let letter = kind.name().chars().next().unwrap_or('?');
+            let second = if kind.is_directional() { arrow } else { ' ' };
+            (format!("{}{}", letter, second), Color::Magenta)
+        }
+    }
+}
+
+impl Viewer {
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char(' ') => self.grid.is_paused = !self.grid.is_paused,
+            KeyCode::Char('n') => self.grid.step(),
+            KeyCode::Left | KeyCode::Char('a') => self.offset.0 -= 1,
+            KeyCode::Right | KeyCode::Char('d') => self.offset.0 += 1,
+            KeyCode::Up | KeyCode::Char('w') => self.offset.1 -= 1,
+            KeyCode::Down | KeyCode::Char('s') => self.offset.1 += 1,
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.grid.tick_interval = (self.grid.tick_interval - 1).max(1);
+            }
+            KeyCode::Char('-') => self.grid.tick_interval = (self.grid.tick_interval + 1).min(MAX_TICK_INTERVAL),
+            _ => {}
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let (view_w, view_h) = (columns as i32 / 2, rows as i32 - 1);
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+
+        for row in 0..view_h.max(0) {
+            let y = self.offset.1 + row;
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+            for column in 0..view_w {
+                let x = self.offset.0 + column;
+                let Some(cell) = (x >= 0 && y >= 0).then(|| self.grid.cell(x as u32, y as u32)).flatten() else {
+                    queue!(out, Print("  "))?;
+                    continue;
+                };
+                let (text, color) = glyph(cell, self.ascii);
+                queue!(out, SetForegroundColor(color), Print(text))?;
+            }
+        }
+
+        let status = format!(
+            "{} | {}x{} | {} frames per tick | space: play/pause  n: step  arrows: pan  +/-: speed  q: quit",
+            if self.grid.is_paused { "Paused" } else { "Playing" },
+            self.grid.width(),
+            self.grid.height(),
+            self.grid.tick_interval,
+        );
+        let status: String = status.chars().take(columns as usize).collect();
+        queue!(out, cursor::MoveTo(0, rows.saturating_sub(1)), ResetColor, Print(status))?;
+        out.flush()
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let mut redraw = true;
+        while !self.quit {
+            let frame_start = Instant::now();
+            if redraw {
+                self.draw(out)?;
+            }
+            redraw = false;
+
+            while let Some(timeout) = FRAME.checked_sub(frame_start.elapsed()) {
+                if !event::poll(timeout)? {
+                    break;
+                }
+                match event::read()? {
+                    Event::Key(key) if key.kind != KeyEventKind::Release => {
+                        self.handle_key(key.code, key.modifiers);
+                        redraw = true;
+                    }
+                    Event::Resize(_, _) => redraw = true,
+                    _ => {}
+                }
+            }
+
+            if !self.grid.is_paused && self.frame.is_multiple_of(self.grid.tick_interval) {
+                redraw = true;
+            }
+            self.grid.update(self.frame);
+            self.frame = self.frame.wrapping_add(1);
+        }
+        Ok(())
+    }
+}
+
+/// Shows `grid` in the terminal until the user quits. `ascii` avoids
+/// characters outside of ASCII for terminals that can't show them.
+pub fn view(mut grid: Grid, ascii: bool) -> io::Result<()> {
+    grid.tick_interval = Settings::load().tick_interval.max(1);
+    grid.interpolate = false;
+    let mut viewer = Viewer {
+        grid,
+        frame: 0,
+        offset: (0, 0),
+        ascii,
+        quit: false,
+    };
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = viewer.run(&mut out);
+    // Give the terminal back even if drawing failed
+    queue!(out, ResetColor, cursor::Show, terminal::LeaveAlternateScreen)?;
+    out.flush()?;
+    terminal::disable_raw_mode()?;
+    result
+}
@@ -0,0 +1,58 @@
+/// Which part of the grid stays in place when it is resized.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// All anchors in reading order, for laying them out as a 3x3 grid.
+    pub const ALL: [Anchor; 9] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+
+    /// How far existing cells move when a `old_w` x `old_h` grid becomes
+    /// `new_w` x `new_h`.
+    pub fn shift(&self, old_w: u32, old_h: u32, new_w: u32, new_h: u32) -> (i32, i32) {
+        let dw = new_w as i32 - old_w as i32;
+        let dh = new_h as i32 - old_h as i32;
+        let x = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => dw.div_euclid(2),
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => dw,
+        };
+        let y = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Left | Anchor::Center | Anchor::Right => dh.div_euclid(2),
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => dh,
+        };
+        (x, y)
+    }
+
+    /// How many of the `occupied` squares fall off the grid when resizing.
+    pub fn cells_lost(&self, occupied: &[(u32, u32)], old_w: u32, old_h: u32, new_w: u32, new_h: u32) -> usize {
+        let (shift_x, shift_y) = self.shift(old_w, old_h, new_w, new_h);
+        occupied
+            .iter()
+            .filter(|(x, y)| {
+                let (new_x, new_y) = (*x as i32 + shift_x, *y as i32 + shift_y);
+                new_x < 0 || new_x >= new_w as i32 || new_y < 0 || new_y >= new_h as i32
+            })
+            .count()
+    }
+}
@@ -0,0 +1,155 @@
+// A readable text form of grids for tests, debugging and pasting around. Each
+// line is a row and cells are separated by spaces:
+//
+//     > # . X
+//     . G^ . 5
+//
+// `.` is empty, `>` `v` `<` `^` are movers facing that way, `#` is a push
+// cell, `X` a wall and `G` followed by an arrow a generator. Any other cell is
+// written as its id, followed by an arrow if it has a direction, e.g. `6>`.
+// Blank lines are ignored. Unlike level codes this doesn't store wrapping.
+
+use super::{cellkind, cells::Cell, directions::Directions, grid::{Grid, MAX_SIZE}};
+
+fn arrow(direction: Directions) -> char {
+    match direction {
+        Directions::Right => '>',
+        Directions::Down => 'v',
+        Directions::Left => '<',
+        Directions::Up => '^',
+    }
+}
+
+fn parse_arrow(arrow: &str) -> Option<Directions> {
+    match arrow {
+        ">" => Some(Directions::Right),
+        "v" => Some(Directions::Down),
+        "<" => Some(Directions::Left),
+        "^" => Some(Directions::Up),
+        _ => None,
+    }
+}
+
+fn cell_token(cell: &Cell) -> String {
+    match cell.id {
+        0 => ".".to_string(),
+        1 => arrow(cell.direction).to_string(),
+        2 => "#".to_string(),
+        3 => format!("G{}", arrow(cell.direction)),
+        4 => "X".to_string(),
+        id if cell.kind().is_directional() => format!("{}{}", id, arrow(cell.direction)),
+        id => id.to_string(),
+    }
+}
+
+fn parse_cell(token: &str) -> Result<Cell, String> {
+    let invalid = || format!("Invalid cell: {}", token);
+    match token {
+        "." => return Ok(Cell::empty()),
+        "#" => return Ok(Cell::new(2, Directions::Right)),
+        "X" => return Ok(Cell::new(4, Directions::Right)),
+        _ => {}
+    }
+    if let Some(direction) = parse_arrow(token) {
+        return Ok(Cell::new(1, direction));
+    }
+    if let Some(arrow) = token.strip_prefix('G') {
+        return parse_arrow(arrow).map(|direction| Cell::new(3, direction)).ok_or_else(invalid);
+    }
+
+    let digits = token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len());
+    let (id, arrow) = token.split_at(digits);
+    let id: u8 = id.parse().map_err(|_| invalid())?;
+    let direction = if arrow.is_empty() { Directions::Right } else { parse_arrow(arrow).ok_or_else(invalid)? };
+    if cellkind::get(id).is_none() {
+        return Err(format!("Unknown cell type: {}", id));
+    }
+    Ok(Cell::new(id, direction))
+}
+
+pub fn encode(grid: &Grid) -> String {
+    let mut text = String::new();
+    for y in 0..grid.height() as usize {
+        let row: Vec<String> = grid.cells.iter().map(|column| cell_token(&column[y])).collect();
+        text.push_str(&row.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+pub fn decode(text: &str) -> Result<Grid, String> {
+    let rows: Vec<Vec<Cell>> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split_whitespace().map(parse_cell).collect())
+        .collect::<Result<_, _>>()?;
+    let Some(width) = rows.first().map(|row| row.len()) else {
+        return Err("Level is empty".to_string());
+    };
+    if let Some(y) = rows.iter().position(|row| row.len() != width) {
+        return Err(format!("Row {} has {} cells instead of {}", y + 1, rows[y].len(), width));
+    }
+    if width > MAX_SIZE as usize || rows.len() > MAX_SIZE as usize {
+        return Err(format!("Level is larger than {}x{}", MAX_SIZE, MAX_SIZE));
+    }
+
+    let mut grid = Grid::new(width as u32, rows.len() as u32)?;
+    for (y, row) in rows.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            grid.set_cell(x as u32, y as u32, *cell)?;
+        }
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    struct Turner;
+
+    impl cellkind::CellKind for Turner {
+        fn id(&self) -> u8 {
+            6
+        }
+
+        fn name(&self) -> &str {
+            "Turner"
+        }
+
+        fn texture(&self) -> &str {
+            ""
+        }
+
+        fn is_directional(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_every_token() {
+        cellkind::register(Rc::new(Turner)).unwrap();
+        let text = "> # . X\n. G^ . 6v\nv < ^ .\n";
+        let grid = decode(text).unwrap();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.cells[3][1], Cell::new(6, Directions::Down));
+        assert_eq!(encode(&grid), text);
+    }
+
+    #[test]
+    fn blank_lines_and_extra_spaces_are_ignored() {
+        let grid = decode("\n  >   .\n\n# X  \n").unwrap();
+        assert_eq!(encode(&grid), "> .\n# X\n");
+    }
+
+    #[test]
+    fn bad_grids_are_rejected() {
+        assert_eq!(decode("").err().unwrap(), "Level is empty");
+        assert_eq!(decode("> .\n#\n").err().unwrap(), "Row 2 has 1 cells instead of 2");
+        assert_eq!(decode("> ?").err().unwrap(), "Invalid cell: ?");
+        assert_eq!(decode("G#").err().unwrap(), "Invalid cell: G#");
+        assert_eq!(decode("9>").err().unwrap(), "Unknown cell type: 9");
+    }
+}
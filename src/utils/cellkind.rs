@@ -0,0 +1,128 @@
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    directions::{Directions, Side},
+    emptycell::EmptyCell,
+    generatorcell::GeneratorCell,
+    grid::Step,
+    levelcode::MAX_CELL_ID,
+    movercell::MoverCell,
+    pushcell::PushCell,
+    wallcell::WallCell,
+};
+
+/// Everything the game knows about one type of cell. Grids only store the id
+/// and direction of each cell and look the rest up here, so a new cell type
+/// is one implementation of this trait plus a line in `builtin`.
+pub trait CellKind {
+    /// Id used in level codes, so it must never change once released.
+    fn id(&self) -> u8;
+
+    fn name(&self) -> &str;
+
+    /// Path of the texture, drawn facing right for directional cells.
+    fn texture(&self) -> &str;
+
+    fn is_directional(&self) -> bool {
+        false
+    }
+
+    /// Whether movers can push this cell along when pushing against `side`.
+    fn is_pushable(&self, _side: Side) -> bool {
+        true
+    }
+
+    /// Whether cells pushed into this one are deleted instead of pushing it.
+    fn destroys_incoming(&self) -> bool {
+        false
+    }
+
+    /// Whether generators can copy this cell.
+    fn is_generatable(&self) -> bool {
+        true
+    }
+
+    /// Whether the player can pick this cell from the hotbar.
+    fn is_placeable(&self) -> bool {
+        true
+    }
+
+    /// Runs once per tick for every cell of this kind. Changes are queued on
+    /// `step` and only applied after every cell had its turn.
+    fn tick(&self, _step: &mut Step, _x: usize, _y: usize, _direction: Directions) {}
+
+    /// What went wrong while ticking, e.g. a failing script, for showing to
+    /// the player.
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+fn builtin() -> Vec<Rc<dyn CellKind>> {
+    vec![
+        Rc::new(EmptyCell),
+        Rc::new(MoverCell),
+        Rc::new(PushCell),
+        Rc::new(GeneratorCell),
+        Rc::new(WallCell),
+    ]
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Vec<Rc<dyn CellKind>>> = RefCell::new(builtin());
+}
+
+/// Adds a cell type. Fails if its id is already taken or too big for level
+/// codes.
+pub fn register(kind: Rc<dyn CellKind>) -> Result<(), String> {
+    if kind.id() > MAX_CELL_ID {
+        return Err(format!("Cell id {} is above the highest id {}", kind.id(), MAX_CELL_ID));
+    }
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if let Some(existing) = registry.iter().find(|existing| existing.id() == kind.id()) {
+            return Err(format!("Cell id {} is already used by {}", kind.id(), existing.name()));
+        }
+        registry.push(kind);
+        registry.sort_by_key(|kind| kind.id());
+        Ok(())
+    })
+}
+
+pub fn get(id: u8) -> Option<Rc<dyn CellKind>> {
+    REGISTRY.with(|registry| registry.borrow().iter().find(|kind| kind.id() == id).cloned())
+}
+
+/// All registered cell types, ordered by id.
+pub fn all() -> Vec<Rc<dyn CellKind>> {
+    REGISTRY.with(|registry| registry.borrow().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Numbered(u8);
+
+    impl CellKind for Numbered {
+        fn id(&self) -> u8 {
+            self.0
+        }
+
+        fn name(&self) -> &str {
+            "numbered"
+        }
+
+        fn texture(&self) -> &str {
+            ""
+        }
+    }
+
+    #[test]
+    fn register_rejects_taken_and_too_big_ids() {
+        assert!(register(Rc::new(Numbered(1))).is_err());
+        assert!(register(Rc::new(Numbered(MAX_CELL_ID + 1))).is_err());
+        assert!(register(Rc::new(Numbered(MAX_CELL_ID))).is_ok());
+        assert!(get(MAX_CELL_ID).is_some());
+    }
+}
@@ -1,19 +1,73 @@
-use super::{emptycell::EmptyCell, generatorcell::GeneratorCell, movercell::MoverCell, pushcell::PushCell};
+use std::rc::Rc;
 
-pub enum Cells {
-    EmptyCell(EmptyCell),
-    MoverCell(MoverCell),
-    PushCell(PushCell),
-    GeneratorCell(GeneratorCell),
+use macroquad::prelude::*;
+
+use super::{cellkind::{self, CellKind}, directions::Directions, textures};
+
+pub const EMPTY: u8 = 0;
+
+/// The contents of one square of a grid. What a cell does is defined by its
+/// `CellKind`, looked up by id.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cell {
+    pub id: u8,
+    pub direction: Directions,
 }
 
-impl Clone for Cells {
-    fn clone(&self) -> Cells {
-        match self {
-            Cells::EmptyCell(cell) => Cells::EmptyCell(cell.clone()),
-            Cells::MoverCell(cell) => Cells::MoverCell(cell.clone()),
-            Cells::PushCell(cell) => Cells::PushCell(cell.clone()),
-            Cells::GeneratorCell(cell) => Cells::GeneratorCell(cell.clone()),
+impl Cell {
+    /// A cell of kind `id`. Cells without a direction always face right, so
+    /// equal cells also compare and encode equal.
+    pub fn new(id: u8, direction: Directions) -> Cell {
+        let directional = cellkind::get(id).is_some_and(|kind| kind.is_directional());
+        Cell {
+            id,
+            direction: if directional { direction } else { Directions::Right },
         }
     }
+
+    pub fn empty() -> Cell {
+        Cell { id: EMPTY, direction: Directions::Right }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.id == EMPTY
+    }
+
+    /// The kind of this cell. Grids only ever hold registered ids.
+    pub fn kind(&self) -> Rc<dyn CellKind> {
+        cellkind::get(self.id).unwrap_or_else(|| panic!("Unregistered cell id {}", self.id))
+    }
+
+    /// One cell of every kind the player can place, in hotbar order.
+    pub fn placeable() -> Vec<Cell> {
+        cellkind::all()
+            .iter()
+            .filter(|kind| kind.is_placeable())
+            .map(|kind| Cell::new(kind.id(), Directions::Right))
+            .collect()
+    }
+
+    /// Texture rotation in radians.
+    pub fn rotation(&self) -> f32 {
+        self.direction.rotation()
+    }
+
+    pub fn texture(&self) -> Texture2D {
+        textures::get(self.kind().texture())
+    }
+
+    /// Draws the cell as a 64x64 square at `(x, y)`, turned by `rotation`.
+    pub fn draw(&self, x: f32, y: f32, rotation: f32) {
+        draw_texture_ex(
+            &self.texture(),
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(64.0, 64.0)),
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
 }
@@ -0,0 +1,222 @@
+use std::{fs, rc::Rc};
+
+use super::{
+    cellkind::{self, CellKind},
+    directions::{Directions, Side},
+    grid::Step,
+    levelcode::MAX_CELL_ID,
+    script::Script,
+};
+
+// Custom cells are `key = value` text files, one cell per file, e.g.
+//
+//     id = 5
+//     name = Rotator
+//     texture = assets/CWrotatorcell.png
+//     directional = false
+//     pushable = front back left right
+//     generatable = true
+//     actions = rotate_neighbours_clockwise
+//     script = cells/drill.rhai
+//
+// `pushable` lists the sides the cell can be pushed from (or `all` / `none`)
+// and `actions` lists what the cell does every tick, see `CellAction`. A
+// `script` runs after the actions, see `script.rs`. Ids 0 to 4 belong to the
+// built-in cells.
+const CELLS_DIR: &str = "cells";
+const EXTENSION: &str = "txt";
+const FIRST_CUSTOM_ID: u8 = 5;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CellAction {
+    MoveForward,
+    RotateNeighboursClockwise,
+    RotateNeighboursCounterClockwise,
+    CopyBehindToFront,
+    // Cells pushed into this one are deleted
+    DestroyIncoming,
+}
+
+impl CellAction {
+    fn parse(name: &str) -> Result<CellAction, String> {
+        match name {
+            "move_forward" => Ok(CellAction::MoveForward),
+            "rotate_neighbours_clockwise" => Ok(CellAction::RotateNeighboursClockwise),
+            "rotate_neighbours_counter_clockwise" => Ok(CellAction::RotateNeighboursCounterClockwise),
+            "copy_behind_to_front" => Ok(CellAction::CopyBehindToFront),
+            "destroy_incoming" => Ok(CellAction::DestroyIncoming),
+            _ => Err(format!("Unknown action: {}", name)),
+        }
+    }
+}
+
+/// A cell type defined by a file in the cells directory.
+pub struct CustomCell {
+    id: u8,
+    name: String,
+    texture: String,
+    directional: bool,
+    pushable_sides: Vec<Side>,
+    generatable: bool,
+    actions: Vec<CellAction>,
+    script: Option<Script>,
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    value.parse().map_err(|_| format!("{} must be true or false", key))
+}
+
+fn parse_sides(value: &str) -> Result<Vec<Side>, String> {
+    match value {
+        "all" => return Ok(vec![Side::Front, Side::Back, Side::Left, Side::Right]),
+        "none" => return Ok(Vec::new()),
+        _ => {}
+    }
+    value
+        .split_whitespace()
+        .map(|side| match side {
+            "front" => Ok(Side::Front),
+            "back" => Ok(Side::Back),
+            "left" => Ok(Side::Left),
+            "right" => Ok(Side::Right),
+            _ => Err(format!("Unknown side: {}", side)),
+        })
+        .collect()
+}
+
+impl CustomCell {
+    pub fn parse(text: &str) -> Result<CustomCell, String> {
+        let mut id = None;
+        let mut name = None;
+        let mut texture = None;
+        let mut cell = CustomCell {
+            id: 0,
+            name: String::new(),
+            texture: String::new(),
+            directional: false,
+            pushable_sides: vec![Side::Front, Side::Back, Side::Left, Side::Right],
+            generatable: true,
+            actions: Vec::new(),
+            script: None,
+        };
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("Expected key = value: {}", line));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "id" => id = Some(value.parse::<u8>().map_err(|_| format!("Invalid id: {}", value))?),
+                "name" => name = Some(value.to_string()),
+                "texture" => texture = Some(value.to_string()),
+                "directional" => cell.directional = parse_bool(key, value)?,
+                "pushable" => cell.pushable_sides = parse_sides(value)?,
+                "generatable" => cell.generatable = parse_bool(key, value)?,
+                "actions" => {
+                    cell.actions = value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|action| !action.is_empty())
+                        .map(CellAction::parse)
+                        .collect::<Result<_, _>>()?
+                }
+                "script" => cell.script = Some(Script::load(value)?),
+                _ => return Err(format!("Unknown key: {}", key)),
+            }
+        }
+
+        cell.id = id.ok_or("Missing id")?;
+        if cell.id < FIRST_CUSTOM_ID || cell.id > MAX_CELL_ID {
+            return Err(format!("Id must be between {} and {}", FIRST_CUSTOM_ID, MAX_CELL_ID));
+        }
+        cell.name = name.ok_or("Missing name")?;
+        cell.texture = texture.ok_or("Missing texture")?;
+        Ok(cell)
+    }
+}
+
+impl CellKind for CustomCell {
+    fn id(&self) -> u8 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn texture(&self) -> &str {
+        &self.texture
+    }
+
+    fn is_directional(&self) -> bool {
+        self.directional
+    }
+
+    fn is_pushable(&self, side: Side) -> bool {
+        self.pushable_sides.contains(&side)
+    }
+
+    fn is_generatable(&self) -> bool {
+        self.generatable
+    }
+
+    fn destroys_incoming(&self) -> bool {
+        self.actions.contains(&CellAction::DestroyIncoming)
+    }
+
+    fn tick(&self, step: &mut Step, x: usize, y: usize, direction: Directions) {
+        for action in &self.actions {
+            match action {
+                CellAction::MoveForward => {
+                    step.push(x, y, direction);
+                }
+                CellAction::RotateNeighboursClockwise | CellAction::RotateNeighboursCounterClockwise => {
+                    let clockwise = *action == CellAction::RotateNeighboursClockwise;
+                    for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+                        if let Some((nx, ny)) = step.neighbour(x, y, dx, dy) {
+                            step.rotate(nx, ny, clockwise);
+                        }
+                    }
+                }
+                CellAction::CopyBehindToFront => step.generate(x, y, direction),
+                CellAction::DestroyIncoming => {}
+            }
+        }
+        if let Some(script) = &self.script {
+            script.run(step, x, y, direction);
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        self.script.as_ref().and_then(Script::error)
+    }
+}
+
+/// Registers every cell definition in the cells directory. Returns a message
+/// for each file that could not be loaded.
+pub fn load_all() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(CELLS_DIR) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == EXTENSION))
+        .collect();
+    paths.sort();
+
+    let mut errors = Vec::new();
+    for path in paths {
+        let result = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| CustomCell::parse(&text))
+            .and_then(|cell| cellkind::register(Rc::new(cell)));
+        if let Err(err) = result {
+            errors.push(format!("{}: {}", path.display(), err));
+        }
+    }
+    errors
+}
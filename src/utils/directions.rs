@@ -1,5 +1,8 @@
 use std::fmt::{Debug, Formatter, Result};
 
+use super::helper::degrees2radians;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Directions {
     Up,
     Down,
@@ -7,17 +10,6 @@ pub enum Directions {
     Right,
 }
 
-impl Clone for Directions {
-    fn clone(&self) -> Directions {
-        match self {
-            Directions::Up => Directions::Up,
-            Directions::Down => Directions::Down,
-            Directions::Left => Directions::Left,
-            Directions::Right => Directions::Right,
-        }
-    }
-}
-
 impl Debug for Directions {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
@@ -28,3 +20,78 @@ impl Debug for Directions {
         }
     }
 }
+
+impl Directions {
+    /// Index in clockwise order starting from Right, as used in level codes.
+    pub fn index(&self) -> u8 {
+        match self {
+            Directions::Right => 0,
+            Directions::Down => 1,
+            Directions::Left => 2,
+            Directions::Up => 3,
+        }
+    }
+
+    pub fn from_index(index: u8) -> Directions {
+        match index % 4 {
+            0 => Directions::Right,
+            1 => Directions::Down,
+            2 => Directions::Left,
+            _ => Directions::Up,
+        }
+    }
+
+    pub fn clockwise(&self) -> Directions {
+        Directions::from_index(self.index() + 1)
+    }
+
+    pub fn counter_clockwise(&self) -> Directions {
+        Directions::from_index(self.index() + 3)
+    }
+
+    /// One square in this direction as `(dx, dy)`, with y growing downwards.
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Directions::Up => (0, -1),
+            Directions::Down => (0, 1),
+            Directions::Left => (-1, 0),
+            Directions::Right => (1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Directions {
+        Directions::from_index(self.index() + 2)
+    }
+
+    /// Texture rotation in radians for a cell facing this direction.
+    pub fn rotation(&self) -> f32 {
+        match self {
+            Directions::Up => degrees2radians(270.0),
+            Directions::Down => degrees2radians(90.0),
+            Directions::Left => degrees2radians(180.0),
+            Directions::Right => degrees2radians(0.0),
+        }
+    }
+}
+
+/// A side of a cell, relative to the way it faces. Cells without a direction
+/// face right.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Side {
+    Front,
+    Back,
+    Left,
+    Right,
+}
+
+impl Side {
+    /// The side of a cell facing `facing` that points towards `towards`.
+    pub fn of(facing: Directions, towards: Directions) -> Side {
+        match (towards.index() + 4 - facing.index()) % 4 {
+            0 => Side::Front,
+            1 => Side::Right,
+            2 => Side::Back,
+            _ => Side::Left,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter, Result};
+
+use super::directions::Directions;
+
+/// Something that happened to a cell during one tick. Positions are the
+/// squares as `(x, y)`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Moved { from: (usize, usize), to: (usize, usize) },
+    // `by` is the square of the cell that made it
+    Generated { at: (usize, usize), by: (usize, usize) },
+    // A cell couldn't be generated because its square stayed full
+    GenerationBlocked { at: (usize, usize), by: (usize, usize) },
+    Rotated { at: (usize, usize), clockwise: bool },
+    Destroyed { at: (usize, usize) },
+    // A cell tried to move but something unpushable or the edge was in the way
+    PushBlocked { at: (usize, usize), direction: Directions },
+}
+
+impl Event {
+    /// The square the event happened in, for moves the one the cell ended up in.
+    pub fn square(&self) -> (usize, usize) {
+        match *self {
+            Event::Moved { to, .. } => to,
+            Event::Generated { at, .. }
+            | Event::GenerationBlocked { at, .. }
+            | Event::Rotated { at, .. }
+            | Event::Destroyed { at }
+            | Event::PushBlocked { at, .. } => at,
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match self {
+            Event::Moved { from, to } => write!(f, "Moved {},{} to {},{}", from.0, from.1, to.0, to.1),
+            Event::Generated { at, by } => write!(f, "Generated at {},{} by {},{}", at.0, at.1, by.0, by.1),
+            Event::GenerationBlocked { at, by } => {
+                write!(f, "Generation at {},{} by {},{} blocked", at.0, at.1, by.0, by.1)
+            }
+            Event::Rotated { at, clockwise } => write!(
+                f,
+                "Rotated {},{} {}",
+                at.0,
+                at.1,
+                if *clockwise { "clockwise" } else { "counter-clockwise" }
+            ),
+            Event::Destroyed { at } => write!(f, "Destroyed at {},{}", at.0, at.1),
+            Event::PushBlocked { at, direction } => write!(f, "Blocked at {},{} going {:?}", at.0, at.1, direction),
+        }
+    }
+}
@@ -1,313 +1,526 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use macroquad::prelude::*;
 
-use super::{cells::Cells, directions::Directions, emptycell::EmptyCell, movercell::MoverCell, pushcell::PushCell, generatorcell::GeneratorCell};
+use super::{anchor::Anchor, cellkind, cells::Cell, directions::{Directions, Side}, events::Event};
+
+// Largest width or height a grid may have.
+pub const MAX_SIZE: u32 = 200;
+
+/// How a cell got to its square in the last tick, so drawing can slide and
+/// turn it there instead of jumping.
+#[derive(Clone, Copy)]
+pub struct Motion {
+    // Squares from the current position back to where the cell was, or to
+    // the generator that made it
+    pub from: (i32, i32),
+    pub from_rotation: f32,
+}
 
 pub struct Grid {
-    pub cells: Vec<Vec<Cells>>,
+    pub cells: Vec<Vec<Cell>>,
     pub draw_offset: (i32, i32),
     pub is_paused: bool,
     pub highlighted_cell: Option<(u32, u32)>,
+    // Cells leaving one edge come back in on the opposite edge
+    pub wrap: bool,
+    // The grid only steps on frames that are a multiple of this
+    pub tick_interval: u32,
+    // Slide cells between squares instead of moving them at once
+    pub interpolate: bool,
+    // Keyed by the square each cell ended up in
+    pub motions: HashMap<(usize, usize), Motion>,
+    // What happened in the last tick, in the order it was applied
+    pub events: Vec<Event>,
+    // Ticks stepped since the grid was made, resized or edited
+    pub ticks: u64,
+    // Number of the cell in each square that stays with it while it moves,
+    // 0 for empty squares
+    identities: Vec<Vec<u64>>,
+    next_identity: u64,
+    frames_since_step: u32,
 }
 
-impl Grid {
-    pub async fn new(w: u32, h: u32) -> Grid {
-        let mut cells = Vec::new();
-        for i in 0..w {
-            let mut row = Vec::new();
-            for j in 0..h {
-                row.push(Cells::EmptyCell(EmptyCell::new(i as f32 * 64.0, j as f32 * 64.0).await));
+/// The changes cells ask for during one tick. Every cell decides from the grid
+/// as it was when the tick started, then all changes are applied together.
+pub struct Step<'a> {
+    grid: &'a Grid,
+    tick: u64,
+    // (from_x, from_y, to_x, to_y)
+    moves: Vec<(usize, usize, usize, usize)>,
+    // Target square, square of the generator and the generated cell
+    new_cells: Vec<(usize, usize, usize, usize, Cell)>,
+    // Square and whether to turn clockwise
+    rotations: Vec<(usize, usize, bool)>,
+    // Cells pushed into a destroying cell or deleted by another cell
+    destroyed: Vec<(usize, usize)>,
+    // Square and direction of pushes that failed
+    blocked: Vec<(usize, usize, Directions)>,
+    // Operations each script has used so far, by script path
+    script_operations: HashMap<String, u64>,
+}
+
+impl Step<'_> {
+    /// Number of this step, counted from 0 like `Grid::ticks`.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    // Scripts share one operation budget per tick between all their cells
+    pub(crate) fn script_operations(&mut self, path: &str) -> &mut u64 {
+        self.script_operations.entry(path.to_string()).or_insert(0)
+    }
+
+    /// The cell at `(x, y)` as it was when the tick started.
+    pub fn cell(&self, x: usize, y: usize) -> Cell {
+        self.grid.cells[x][y]
+    }
+
+    /// Same as `Grid::neighbour`, on the grid as it was when the tick started.
+    pub fn neighbour(&self, x: usize, y: usize, dx: i32, dy: i32) -> Option<(usize, usize)> {
+        self.grid.neighbour(x, y, dx, dy)
+    }
+
+    /// Moves the cell at `(x, y)` one square towards `direction`, pushing the
+    /// cells in front of it along. Returns false if something unpushable or the
+    /// edge of the grid is in the way.
+    pub fn push(&mut self, x: usize, y: usize, direction: Directions) -> bool {
+        let pushed = self.push_chain(x, y, direction);
+        if !pushed {
+            self.blocked.push((x, y, direction));
+        }
+        pushed
+    }
+
+    fn push_chain(&mut self, x: usize, y: usize, direction: Directions) -> bool {
+        let (dx, dy) = direction.offset();
+        let (grid_rows, grid_cols) = (self.grid.cells.len(), self.grid.cells[0].len());
+        let mut chain = vec![(x, y)];
+        let (mut current_x, mut current_y) = (x, y);
+
+        loop {
+            let Some(next) = self.grid.neighbour(current_x, current_y, dx, dy) else {
+                return false;
+            };
+            // A full row of cells on a wrapping grid has nowhere to go
+            if chain.len() > grid_rows.max(grid_cols) {
+                return false;
+            }
+            (current_x, current_y) = next;
+
+            let cell = self.grid.cells[current_x][current_y];
+            if cell.is_empty() {
+                chain.push(next);
+                break;
             }
-            cells.push(row);
+            let kind = cell.kind();
+            if kind.destroys_incoming() {
+                // The front cell of the chain is deleted and the rest moves up
+                self.destroyed.push(*chain.last().unwrap());
+                if chain.len() == 1 {
+                    return true;
+                }
+                break;
+            }
+            if !kind.is_pushable(Side::of(cell.direction, direction.opposite())) {
+                return false;
+            }
+            chain.push(next);
         }
-        Grid {
-            cells,
+
+        // The pushing cell moves first, then the rest of the chain from the far end
+        self.moves.push((x, y, chain[1].0, chain[1].1));
+        for i in (2..chain.len()).rev() {
+            let (from_x, from_y) = chain[i - 1];
+            let (to_x, to_y) = chain[i];
+            self.moves.push((from_x, from_y, to_x, to_y));
+        }
+        true
+    }
+
+    /// Copies the cell behind `(x, y)` into the square in front of it, if that
+    /// square is still empty once everything has moved.
+    pub fn generate(&mut self, x: usize, y: usize, direction: Directions) {
+        let (dx, dy) = direction.offset();
+        let Some((behind_x, behind_y)) = self.grid.neighbour(x, y, -dx, -dy) else {
+            return;
+        };
+        let Some((front_x, front_y)) = self.grid.neighbour(x, y, dx, dy) else {
+            return;
+        };
+        let behind = self.grid.cells[behind_x][behind_y];
+        if !behind.is_empty() && behind.kind().is_generatable() {
+            self.new_cells.push((front_x, front_y, x, y, behind));
+        }
+    }
+
+    /// Turns the cell at `(x, y)` a quarter turn. Cells without a direction
+    /// are left alone.
+    pub fn rotate(&mut self, x: usize, y: usize, clockwise: bool) {
+        self.rotations.push((x, y, clockwise));
+    }
+
+    /// Puts `cell` into `(x, y)` if that square is still empty once everything
+    /// has moved. `from` is the square the cell appears to come out of.
+    pub fn spawn(&mut self, x: usize, y: usize, from: (usize, usize), cell: Cell) {
+        self.new_cells.push((x, y, from.0, from.1, cell));
+    }
+
+    /// Removes the cell at `(x, y)`, and stops it from moving this tick.
+    pub fn delete(&mut self, x: usize, y: usize) {
+        self.destroyed.push((x, y));
+    }
+}
+
+// Both sides have to be between 1 and MAX_SIZE, as the rest of the grid
+// expects at least one square
+fn check_size(w: u32, h: u32) -> Result<(), String> {
+    if w == 0 || h == 0 {
+        Err("Grid is empty".to_string())
+    } else if w > MAX_SIZE || h > MAX_SIZE {
+        Err(format!("Grid is larger than {}x{}", MAX_SIZE, MAX_SIZE))
+    } else {
+        Ok(())
+    }
+}
+
+impl Grid {
+    /// An empty, paused grid. Fails if a side is 0 or larger than `MAX_SIZE`.
+    pub fn new(w: u32, h: u32) -> Result<Grid, String> {
+        check_size(w, h)?;
+        Ok(Grid {
+            cells: vec![vec![Cell::empty(); h as usize]; w as usize],
             draw_offset: (0, 0),
             is_paused: true,
             highlighted_cell: None,
+            wrap: false,
+            tick_interval: 10,
+            interpolate: true,
+            motions: HashMap::new(),
+            events: Vec::new(),
+            ticks: 0,
+            identities: vec![vec![0; h as usize]; w as usize],
+            next_identity: 1,
+            frames_since_step: 0,
+        })
+    }
+
+    /// The number that identifies the cell at `(x, y)` across ticks, or None
+    /// for empty squares. A generated or placed cell gets a new one, a moving
+    /// cell keeps its own.
+    pub fn identity(&self, x: usize, y: usize) -> Option<u64> {
+        let identity = *self.identities.get(x)?.get(y)?;
+        let cell = self.cell(x as u32, y as u32)?;
+        (identity != 0 && !cell.is_empty()).then_some(identity)
+    }
+
+    fn new_identity(&mut self) -> u64 {
+        let identity = self.next_identity;
+        self.next_identity += 1;
+        identity
+    }
+
+    // Numbers the cells that were put in `cells` directly since the last step
+    fn sync_identities(&mut self) {
+        if self.identities.len() != self.cells.len() || self.identities[0].len() != self.cells[0].len() {
+            self.identities = vec![vec![0; self.cells[0].len()]; self.cells.len()];
+        }
+        for x in 0..self.cells.len() {
+            for y in 0..self.cells[0].len() {
+                if self.cells[x][y].is_empty() {
+                    self.identities[x][y] = 0;
+                } else if self.identities[x][y] == 0 {
+                    self.identities[x][y] = self.new_identity();
+                }
+            }
         }
     }
 
-    pub fn set_cell(&mut self, x: u32, y: u32, cell: Cells) {
+    /// The square `(dx, dy)` away from `(x, y)`, or None if that is outside
+    /// the grid and wrapping is off.
+    pub fn neighbour(&self, x: usize, y: usize, dx: i32, dy: i32) -> Option<(usize, usize)> {
+        let (w, h) = (self.cells.len() as i32, self.cells[0].len() as i32);
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        if self.wrap {
+            Some((nx.rem_euclid(w) as usize, ny.rem_euclid(h) as usize))
+        } else if nx < 0 || nx >= w || ny < 0 || ny >= h {
+            None
+        } else {
+            Some((nx as usize, ny as usize))
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.cells.len() as u32
+    }
+
+    pub fn height(&self) -> u32 {
+        self.cells[0].len() as u32
+    }
+
+    /// The cell at `(x, y)`, or None outside the grid.
+    pub fn cell(&self, x: u32, y: u32) -> Option<Cell> {
+        self.cells.get(x as usize).and_then(|column| column.get(y as usize)).copied()
+    }
+
+    /// Positions of all non-empty cells.
+    pub fn occupied(&self) -> Vec<(u32, u32)> {
+        let mut occupied = Vec::new();
+        for (x, column) in self.cells.iter().enumerate() {
+            for (y, cell) in column.iter().enumerate() {
+                if !cell.is_empty() {
+                    occupied.push((x as u32, y as u32));
+                }
+            }
+        }
+        occupied
+    }
+
+    /// Changes the size of the grid, keeping the cells that still fit. The
+    /// anchor decides which side grows or shrinks. Fails for the same sizes as
+    /// `new`.
+    pub fn resize(&mut self, w: u32, h: u32, anchor: Anchor) -> Result<(), String> {
+        check_size(w, h)?;
+        let (shift_x, shift_y) = anchor.shift(self.cells.len() as u32, self.cells[0].len() as u32, w, h);
+        let mut cells = Vec::new();
+        let mut identities = Vec::new();
+        for x in 0..w as i32 {
+            let mut column = Vec::new();
+            let mut identity_column = Vec::new();
+            for y in 0..h as i32 {
+                let (old_x, old_y) = (x - shift_x, y - shift_y);
+                let old = if old_x >= 0 && old_y >= 0 {
+                    self.cells.get(old_x as usize).and_then(|old_column| old_column.get(old_y as usize)).zip(
+                        self.identities.get(old_x as usize).and_then(|old_column| old_column.get(old_y as usize)),
+                    )
+                } else {
+                    None
+                };
+                column.push(old.map_or(Cell::empty(), |(cell, _)| *cell));
+                identity_column.push(old.map_or(0, |(_, identity)| *identity));
+            }
+            cells.push(column);
+            identities.push(identity_column);
+        }
+        self.cells = cells;
+        self.identities = identities;
+        self.highlighted_cell = None;
+        self.motions.clear();
+        self.events.clear();
+        self.ticks = 0;
+        Ok(())
+    }
+
+    /// Puts `cell` at `(x, y)`. Fails outside the grid or for cell ids that
+    /// aren't registered.
+    pub fn set_cell(&mut self, x: u32, y: u32, cell: Cell) -> Result<(), String> {
+        if x >= self.width() || y >= self.height() {
+            return Err(format!("Square {},{} is outside the {}x{} grid", x, y, self.width(), self.height()));
+        }
+        if cellkind::get(cell.id).is_none() {
+            return Err(format!("Unknown cell type: {}", cell.id));
+        }
         self.cells[x as usize][y as usize] = cell;
+        self.identities[x as usize][y as usize] = if cell.is_empty() { 0 } else { self.new_identity() };
+        self.motions.remove(&(x as usize, y as usize));
+        Ok(())
     }
 
     pub fn set_draw_offset(&mut self, x: i32, y: i32) {
         self.draw_offset = (x, y);
-        for (i, row) in self.cells.iter_mut().enumerate() {
-            for (j, cell) in row.iter_mut().enumerate() {
-                match cell {
-                    Cells::EmptyCell(emptycell) => emptycell.set_position(
-                        (i as u32 * 64) as f32 + x as f32,
-                        (j as u32 * 64) as f32 + y as f32,
-                    ),
-                    Cells::MoverCell(movercell) => movercell.set_position(
-                        (i as u32 * 64) as f32 + x as f32,
-                        (j as u32 * 64) as f32 + y as f32,
-                    ),
-                    Cells::PushCell(pushcell) => pushcell.set_position(
-                        (i as u32 * 64) as f32 + x as f32,
-                        (j as u32 * 64) as f32 + y as f32,
-                    ),
-                    Cells::GeneratorCell(generatorcell) => generatorcell.set_position(
-                        (i as u32 * 64) as f32 + x as f32,
-                        (j as u32 * 64) as f32 + y as f32,
-                    ),
-                    _ => {}
-                }
+    }
+
+    // Offset from `to` back to `from` for a one square step, going the short
+    // way round when the step wrapped over an edge
+    fn step_offset(&self, from: (usize, usize), to: (usize, usize)) -> (i32, i32) {
+        let wrapped = |d: i32, size: usize| {
+            if d > 1 {
+                d - size as i32
+            } else if d < -1 {
+                d + size as i32
+            } else {
+                d
             }
-        }
+        };
+        (
+            wrapped(from.0 as i32 - to.0 as i32, self.cells.len()),
+            wrapped(from.1 as i32 - to.1 as i32, self.cells[0].len()),
+        )
     }
 
-    pub async fn update(&mut self, tick: u32) {
-        if tick % 10 != 0 {
-            return;
+    /// Called once per frame. Steps the grid every `tick_interval` frames
+    /// while it isn't paused, and returns whether it stepped.
+    pub fn update(&mut self, tick: u32) -> bool {
+        self.frames_since_step = self.frames_since_step.saturating_add(1);
+        if !tick.is_multiple_of(self.tick_interval) {
+            return false;
         }
         if self.is_paused {
-            return;
+            return false;
         }
+        self.step();
+        true
+    }
 
-        let grid_rows = self.cells.len();
-        let grid_cols = self.cells[0].len();
-        let mut moves: Vec<(usize, usize, usize, usize)> = Vec::new();
-        let mut new_cells: Vec<(usize, usize, Cells)> = Vec::new();
-
-        // First collect all potential moves
-        for x in 0..grid_rows {
-            for y in 0..grid_cols {
-                if let Cells::GeneratorCell(generator) = &self.cells[x][y] {
-                    let (behind_x, behind_y) = match generator.direction {
-                        Directions::Up => (x, y + 1),
-                        Directions::Down => (x, y - 1),
-                        Directions::Left => (x + 1, y),
-                        Directions::Right => (x - 1, y),
-                    };
-
-                    let (front_x, front_y) = match generator.direction {
-                        Directions::Up => (x, y - 1),
-                        Directions::Down => (x, y + 1),
-                        Directions::Left => (x - 1, y),
-                        Directions::Right => (x + 1, y),
-                    };
-
-                    // If the behind cell is empty or its out of bounds, skip
-                    if behind_x < 0 || behind_x >= grid_rows || behind_y < 0 || behind_y >= grid_cols {
-                        continue;
-                    }
-                    if let Cells::EmptyCell(_) = &self.cells[behind_x as usize][behind_y as usize] {
-                        moves.push((x, y, behind_x as usize, behind_y as usize));
-                    }
-                    
-                    // If the front cell is out of bounds, skip
-                    if front_x < 0 || front_x >= grid_rows || front_y < 0 || front_y >= grid_cols {
-                        continue;
-                    }
-
-                    // Copy the behind cell to the front cell
-                    match &self.cells[behind_x as usize][behind_y as usize] {
-                        Cells::MoverCell(mover) => {
-                            new_cells.push((front_x as usize, front_y as usize, Cells::MoverCell(MoverCell::new(
-                                front_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                                front_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                                Some(mover.direction.clone())
-                            ).await)));
-                        }
-                        Cells::PushCell(_) => {
-                            new_cells.push((front_x as usize, front_y as usize, Cells::PushCell(PushCell::new(
-                                front_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                                front_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                                None
-                            ).await)));
-                        }
-                        Cells::GeneratorCell(generator) => {
-                            new_cells.push((front_x as usize, front_y as usize, Cells::GeneratorCell(GeneratorCell::new(
-                                front_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                                front_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                                Some(generator.direction.clone())
-                            ).await)));
-                        }
-                        _ => {}
-                    }
-                }
+    /// Advances the grid by one tick right away, whether it is paused or not.
+    pub fn step(&mut self) {
+        self.frames_since_step = 0;
+        self.motions.clear();
+        self.sync_identities();
 
-                if let Cells::MoverCell(mover) = &self.cells[x][y] {
-                    let (dx, dy) = match mover.direction {
-                        Directions::Up => (0, -1),
-                        Directions::Down => (0, 1),
-                        Directions::Left => (-1, 0),
-                        Directions::Right => (1, 0),
-                    };
-
-                    let (new_x, new_y) = (x as i32 + dx, y as i32 + dy);
-
-                    // Check boundaries
-                    if new_x < 0 || new_x >= grid_rows as i32 || new_y < 0 || new_y >= grid_cols as i32 {
-                        continue;
-                    }
-
-                    let (ux, uy) = (new_x as usize, new_y as usize);
-                    
-                    // Check what's in the target cell
-                    match &self.cells[ux][uy] {
-                        Cells::EmptyCell(_) => {
-                            moves.push((x, y, ux, uy));
-                        }
-                        Cells::PushCell(_) | Cells::MoverCell(_) => {
-                            // Check if we can push the entire chain
-                            let mut push_chain = vec![(ux, uy)];
-                            let mut current_x = new_x;
-                            let mut current_y = new_y;
-                            let mut can_push = true;
-
-                            while can_push {
-                                current_x += dx;
-                                current_y += dy;
-
-                                if current_x < 0 || current_x >= grid_rows as i32 ||
-                                   current_y < 0 || current_y >= grid_cols as i32 {
-                                    can_push = false;
-                                    break;
-                                }
-
-                                match &self.cells[current_x as usize][current_y as usize] {
-                                    Cells::EmptyCell(_) => {
-                                        push_chain.push((current_x as usize, current_y as usize));
-                                        break;
-                                    }
-                                    Cells::PushCell(_) | Cells::MoverCell(_) | Cells::GeneratorCell(_) => {
-                                        push_chain.push((current_x as usize, current_y as usize));
-                                    }
-                                    _ => {
-                                        can_push = false;
-                                        break;
-                                    }
-                                }
-                            }
-
-                            if can_push {
-                                // Add mover cell move
-                                moves.push((x, y, ux, uy));
-                                
-                                // Add push cell moves in reverse order
-                                for i in (0..push_chain.len()).rev() {
-                                    if i == 0 {
-                                        continue;
-                                    }
-                                    let (from_x, from_y) = push_chain[i-1];
-                                    let (to_x, to_y) = push_chain[i];
-                                    moves.push((from_x, from_y, to_x, to_y));
-                                }
-                            }
-                        }
-                        _ => {} // Blocked by other cell type
-                    }
+        // First collect what every cell wants to do
+        let mut step = Step {
+            grid: self,
+            tick: self.ticks,
+            moves: Vec::new(),
+            new_cells: Vec::new(),
+            rotations: Vec::new(),
+            destroyed: Vec::new(),
+            blocked: Vec::new(),
+            script_operations: HashMap::new(),
+        };
+        for x in 0..self.cells.len() {
+            for y in 0..self.cells[0].len() {
+                let cell = self.cells[x][y];
+                if !cell.is_empty() {
+                    cell.kind().tick(&mut step, x, y, cell.direction);
                 }
             }
         }
+        let Step { mut moves, new_cells, rotations, destroyed, blocked, .. } = step;
+        let mut events: Vec<Event> = blocked
+            .into_iter()
+            .map(|(x, y, direction)| Event::PushBlocked { at: (x, y), direction })
+            .collect();
+
+        // Rotated cells turn before moving, remembering how they faced for drawing
+        let mut old_rotations = HashMap::new();
+        for (x, y, clockwise) in rotations {
+            let cell = self.cells[x][y];
+            if cell.is_empty() || !cell.kind().is_directional() {
+                continue;
+            }
+            old_rotations.entry((x, y)).or_insert(cell.rotation());
+            let direction = if clockwise { cell.direction.clockwise() } else { cell.direction.counter_clockwise() };
+            self.cells[x][y] = Cell::new(cell.id, direction);
+            events.push(Event::Rotated { at: (x, y), clockwise });
+        }
 
         // Apply moves in reverse order to prevent overwriting
         moves.reverse();
 
         // Save the current state of the grid
         let grid_state = self.cells.clone();
+        let identity_state = self.identities.clone();
 
         // Clear original positions first
         let mut to_clear = HashSet::new();
         for (from_x, from_y, _to_x, _to_y) in &moves {
             to_clear.insert((*from_x, *from_y));
         }
+        to_clear.extend(destroyed.iter().copied());
 
         for (x, y) in &to_clear {
-            self.cells[*x][*y] = Cells::EmptyCell(EmptyCell::new(
-                *x as f32 * 64.0 + self.draw_offset.0 as f32,
-                *y as f32 * 64.0 + self.draw_offset.1 as f32
-            ).await);
+            self.cells[*x][*y] = Cell::empty();
+            self.identities[*x][*y] = 0;
+        }
+        let mut already_destroyed = HashSet::new();
+        for (x, y) in destroyed.iter().copied() {
+            if !grid_state[x][y].is_empty() && already_destroyed.insert((x, y)) {
+                events.push(Event::Destroyed { at: (x, y) });
+            }
         }
 
         let mut already_moved_cells: Vec<(u32, u32)> = Vec::new();
 
-        // Create new cells at target positions with original properties
+        // Move the cells to their target positions
         for (from_x, from_y, to_x, to_y) in moves {
-            if already_moved_cells.contains(&(from_x as u32, from_y as u32)) {
+            if already_moved_cells.contains(&(from_x as u32, from_y as u32)) || destroyed.contains(&(from_x, from_y)) {
                 continue;
             }
-            match &grid_state[from_x][from_y] {
-                Cells::MoverCell(mover) => {
-                    self.cells[to_x][to_y] = Cells::MoverCell(MoverCell::new(
-                        to_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                        to_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                        Some(mover.direction.clone())
-                    ).await);
-                }
-                Cells::PushCell(_) => {
-                    self.cells[to_x][to_y] = Cells::PushCell(PushCell::new(
-                        to_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                        to_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                        None
-                    ).await);
-                }
-                Cells::GeneratorCell(generator) => {
-                    self.cells[to_x][to_y] = Cells::GeneratorCell(GeneratorCell::new(
-                        to_x as f32 * 64.0 + self.draw_offset.0 as f32,
-                        to_y as f32 * 64.0 + self.draw_offset.1 as f32,
-                        Some(generator.direction.clone())
-                    ).await);
-                }
-                _ => {}
-            }
+            let cell = grid_state[from_x][from_y];
+            self.cells[to_x][to_y] = cell;
+            self.identities[to_x][to_y] = identity_state[from_x][from_y];
+            self.motions.insert((to_x, to_y), Motion {
+                from: self.step_offset((from_x, from_y), (to_x, to_y)),
+                from_rotation: old_rotations.get(&(from_x, from_y)).copied().unwrap_or(cell.rotation()),
+            });
             already_moved_cells.push((from_x as u32, from_y as u32));
+            events.push(Event::Moved { from: (from_x, from_y), to: (to_x, to_y) });
         }
+
+        // Cells that only turned still get animated
+        for ((x, y), from_rotation) in old_rotations {
+            if !already_moved_cells.contains(&(x as u32, y as u32)) {
+                self.motions.entry((x, y)).or_insert(Motion { from: (0, 0), from_rotation });
+            }
+        }
+
+        // Generated cells only appear in squares that are still free after moving
+        for (to_x, to_y, generator_x, generator_y, cell) in new_cells {
+            if !self.cells[to_x][to_y].is_empty() {
+                events.push(Event::GenerationBlocked { at: (to_x, to_y), by: (generator_x, generator_y) });
+                continue;
+            }
+            self.cells[to_x][to_y] = cell;
+            self.identities[to_x][to_y] = self.new_identity();
+            self.motions.insert((to_x, to_y), Motion {
+                from: self.step_offset((generator_x, generator_y), (to_x, to_y)),
+                from_rotation: cell.rotation(),
+            });
+            events.push(Event::Generated { at: (to_x, to_y), by: (generator_x, generator_y) });
+        }
+        self.events = events;
+        self.ticks += 1;
+    }
+
+    /// How far moving cells are between their old and new squares, from 0 to 1.
+    fn progress(&self) -> f32 {
+        if !self.interpolate {
+            return 1.0;
+        }
+        ((self.frames_since_step + 1) as f32 / self.tick_interval as f32).min(1.0)
     }
 
     pub fn draw(&self) {
-        for row in &self.cells {
-            for cell in row {
+        let progress = self.progress();
+        let empty = Cell::empty();
+        for (x, row) in self.cells.iter().enumerate() {
+            for (y, cell) in row.iter().enumerate() {
+                let draw_x = x as f32 * 64.0 + self.draw_offset.0 as f32;
+                let draw_y = y as f32 * 64.0 + self.draw_offset.1 as f32;
+
                 // If the cell is out of the screen, do not draw it
-                if let Cells::EmptyCell(emptycell) = cell {
-                    if emptycell.x < -64.0
-                        || emptycell.x > screen_width() + 64.0
-                        || emptycell.y < -64.0
-                        || emptycell.y > screen_height() + 64.0
-                    {
-                        continue;
-                    }
+                if draw_x < -64.0 || draw_x > screen_width() + 64.0 || draw_y < -64.0 || draw_y > screen_height() + 64.0 {
+                    continue;
                 }
-                if let Cells::MoverCell(movercell) = cell {
-                    if movercell.x < -64.0
-                        || movercell.x > screen_width() + 64.0
-                        || movercell.y < -64.0
-                        || movercell.y > screen_height() + 64.0
-                    {
-                        continue;
-                    }
-                }
-                if let Cells::PushCell(pushcell) = cell {
-                    if pushcell.x < -64.0
-                        || pushcell.x > screen_width() + 64.0
-                        || pushcell.y < -64.0
-                        || pushcell.y > screen_height() + 64.0
-                    {
-                        continue;
-                    }
-                }
-                if let Cells::GeneratorCell(generatorcell) = cell {
-                    if generatorcell.x < -64.0
-                        || generatorcell.x > screen_width() + 64.0
-                        || generatorcell.y < -64.0
-                        || generatorcell.y > screen_height() + 64.0
-                    {
-                        continue;
-                    }
+
+                // Moving cells are drawn on top afterwards, over an empty square
+                if progress < 1.0 && self.motions.contains_key(&(x, y)) {
+                    empty.draw(draw_x, draw_y, 0.0);
+                    continue;
                 }
 
-                match cell {
-                    Cells::EmptyCell(emptycell) => emptycell.draw(),
-                    Cells::MoverCell(movercell) => movercell.draw(),
-                    Cells::PushCell(pushcell) => pushcell.draw(),
-                    Cells::GeneratorCell(generatorcell) => generatorcell.draw(),
-                    _ => {}
+                cell.draw(draw_x, draw_y, cell.rotation());
+            }
+        }
+
+        if progress < 1.0 {
+            for (&(x, y), motion) in &self.motions {
+                let cell = &self.cells[x][y];
+                // Turn the short way round
+                let mut turn = cell.rotation() - motion.from_rotation;
+                if turn > std::f32::consts::PI {
+                    turn -= std::f32::consts::TAU;
+                } else if turn < -std::f32::consts::PI {
+                    turn += std::f32::consts::TAU;
                 }
+                let t = 1.0 - progress;
+                cell.draw(
+                    (x as f32 + motion.from.0 as f32 * t) * 64.0 + self.draw_offset.0 as f32,
+                    (y as f32 + motion.from.1 as f32 * t) * 64.0 + self.draw_offset.1 as f32,
+                    motion.from_rotation + turn * progress,
+                );
             }
         }
 
@@ -0,0 +1,162 @@
+// Level codes store a whole grid on one line of text:
+//
+//     CMMM;<width>;<height>;<cells>[;wrap]
+//
+// The cells are listed row by row from the top left. Each cell is one
+// character of ALPHABET at index `id * 4 + direction`, and a cell followed by
+// `(n)` stands for n copies of it, so empty space stays short. A trailing
+// `wrap` marks a grid whose edges wrap around.
+
+use super::{cells::Cell, directions::Directions, grid::{Grid, MAX_SIZE}};
+
+const HEADER: &str = "CMMM";
+const WRAP_FLAG: &str = "wrap";
+const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ!$%&+-.=?^{}";
+
+// Highest cell id that still fits in one character
+pub const MAX_CELL_ID: u8 = (ALPHABET.len() / 4 - 1) as u8;
+
+fn cell_char(cell: &Cell) -> char {
+    ALPHABET[(cell.id * 4 + cell.direction.index()) as usize] as char
+}
+
+pub fn encode(grid: &Grid) -> String {
+    let width = grid.cells.len();
+    let height = grid.cells[0].len();
+
+    let mut chars = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for column in &grid.cells {
+            chars.push(cell_char(&column[y]));
+        }
+    }
+
+    let mut cells = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let mut run = 1;
+        while i + run < chars.len() && chars[i + run] == chars[i] {
+            run += 1;
+        }
+        cells.push(chars[i]);
+        if run > 1 {
+            cells.push_str(&format!("({})", run));
+        }
+        i += run;
+    }
+
+    let mut code = format!("{};{};{};{}", HEADER, width, height, cells);
+    if grid.wrap {
+        code.push(';');
+        code.push_str(WRAP_FLAG);
+    }
+    code
+}
+
+pub fn decode(code: &str) -> Result<Grid, String> {
+    let parts: Vec<&str> = code.trim().split(';').collect();
+    if !(parts.len() == 4 || parts.len() == 5 && parts[4] == WRAP_FLAG) || parts[0] != HEADER {
+        return Err("Not a level code".to_string());
+    }
+    let width: u32 = parts[1].parse().map_err(|_| format!("Invalid width: {}", parts[1]))?;
+    let height: u32 = parts[2].parse().map_err(|_| format!("Invalid height: {}", parts[2]))?;
+    if width == 0 || height == 0 {
+        return Err("Level is empty".to_string());
+    }
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(format!("Level is larger than {}x{}", MAX_SIZE, MAX_SIZE));
+    }
+
+    let size = (width as usize)
+        .checked_mul(height as usize)
+        .ok_or(format!("Level is larger than {}x{}", MAX_SIZE, MAX_SIZE))?;
+    let mut values = Vec::with_capacity(size);
+    let mut chars = parts[3].chars().peekable();
+    while let Some(c) = chars.next() {
+        let value = ALPHABET
+            .iter()
+            .position(|a| *a as char == c)
+            .ok_or(format!("Invalid cell: {}", c))? as u8;
+        let mut run = 1;
+        if chars.peek() == Some(&'(') {
+            chars.next();
+            let digits: String = chars.by_ref().take_while(|c| *c != ')').collect();
+            run = digits.parse().map_err(|_| format!("Invalid repeat count: {}", digits))?;
+        }
+        // Crafted codes can have runs long enough to overflow
+        if values.len().checked_add(run).is_none_or(|total| total > size) {
+            return Err(format!("More than {} cells", size));
+        }
+        for _ in 0..run {
+            values.push(value);
+        }
+    }
+    if values.len() != size {
+        return Err(format!("Expected {} cells but found {}", size, values.len()));
+    }
+
+    let mut grid = Grid::new(width, height)?;
+    grid.wrap = parts.len() == 5;
+    for y in 0..height {
+        for x in 0..width {
+            let value = values[y as usize * width as usize + x as usize];
+            if value == 0 {
+                continue;
+            }
+            grid.set_cell(x, y, Cell::new(value / 4, Directions::from_index(value % 4)))?;
+        }
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_cells_and_wrap() {
+        let mut grid = Grid::new(3, 2).unwrap();
+        grid.wrap = true;
+        grid.set_cell(0, 0, Cell::new(1, Directions::Down)).unwrap();
+        grid.set_cell(1, 0, Cell::new(2, Directions::Right)).unwrap();
+        grid.set_cell(2, 1, Cell::new(3, Directions::Up)).unwrap();
+        grid.set_cell(0, 1, Cell::new(4, Directions::Right)).unwrap();
+
+        let code = encode(&grid);
+        let decoded = decode(&code).unwrap();
+        assert_eq!(decoded.cells, grid.cells);
+        assert!(decoded.wrap);
+        assert_eq!(encode(&decoded), code);
+    }
+
+    #[test]
+    fn repeated_cells_are_run_length_encoded() {
+        let mut grid = Grid::new(4, 4).unwrap();
+        assert_eq!(encode(&grid), "CMMM;4;4;0(16)");
+        grid.set_cell(3, 3, Cell::new(1, Directions::Right)).unwrap();
+        assert_eq!(encode(&grid), "CMMM;4;4;0(15)4");
+        assert_eq!(decode("CMMM;4;4;0(15)4").unwrap().cells, grid.cells);
+    }
+
+    #[test]
+    fn overflowing_runs_are_rejected() {
+        assert_eq!(decode("CMMM;2;2;0(3)0(18446744073709551615)").err().unwrap(), "More than 4 cells");
+        assert_eq!(decode("CMMM;2;2;0(5)").err().unwrap(), "More than 4 cells");
+        assert!(decode("CMMM;2;2;0(99999999999999999999999)").is_err());
+    }
+
+    #[test]
+    fn wrong_cell_counts_are_rejected() {
+        assert_eq!(decode("CMMM;2;2;0(3)").err().unwrap(), "Expected 4 cells but found 3");
+        assert_eq!(decode("CMMM;2;2;").err().unwrap(), "Expected 4 cells but found 0");
+    }
+
+    #[test]
+    fn unknown_characters_are_rejected() {
+        assert_eq!(decode("CMMM;1;1;~").err().unwrap(), "Invalid cell: ~");
+        assert_eq!(decode("CMMM;1;1;0(x)").err().unwrap(), "Invalid repeat count: x");
+        // A valid character for an id no cell type uses
+        assert!(decode("CMMM;1;1;.").is_err());
+        assert_eq!(decode("MMMC;1;1;0").err().unwrap(), "Not a level code");
+    }
+}
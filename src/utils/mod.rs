@@ -1,8 +1,17 @@
-pub mod helper;
+pub(crate) mod helper;
 pub mod directions;
 pub mod emptycell;
 pub mod movercell;
 pub mod pushcell;
 pub mod generatorcell;
+pub mod wallcell;
+pub mod cellkind;
 pub mod cells;
+pub mod customcell;
+pub(crate) mod script;
+pub(crate) mod textures;
+pub mod events;
 pub mod grid;
+pub mod levelcode;
+pub mod asciigrid;
+pub mod anchor;
@@ -1,48 +1,34 @@
-use macroquad::prelude::*;
+use super::{cellkind::CellKind, directions::Directions, generatorcell::GeneratorCell, grid::Step};
 
-use super::{directions::Directions, helper::degrees2radians};
+/// Moves one square forward every tick, pushing whatever is in front of it.
+pub struct MoverCell;
 
-#[derive(Clone)]
-pub struct MoverCell {
-    pub x: f32,
-    pub y: f32,
-    pub texture: Texture2D,
-    pub direction: Directions,
-}
+impl CellKind for MoverCell {
+    fn id(&self) -> u8 {
+        1
+    }
 
-impl MoverCell {
-    pub async fn new(x: f32, y: f32, dir: Option<Directions>) -> MoverCell {
-        let texture = load_texture("assets/movercell.png").await.unwrap();
-        texture.set_filter(FilterMode::Nearest);
-        MoverCell {
-            x,
-            y,
-            texture,
-            direction: dir.unwrap_or(Directions::Right),
-        }
+    fn name(&self) -> &str {
+        "Mover"
     }
 
-    pub fn set_position(&mut self, x: f32, y: f32) {
-        self.x = x;
-        self.y = y;
+    fn texture(&self) -> &str {
+        "assets/movercell.png"
     }
 
-    pub fn draw(&self) {
-        draw_texture_ex(
-            &self.texture,
-            self.x,
-            self.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(64.0, 64.0)),
-                rotation: match self.direction {
-                    Directions::Up => degrees2radians(270.0),
-                    Directions::Down => degrees2radians(90.0),
-                    Directions::Left => degrees2radians(180.0),
-                    Directions::Right => degrees2radians(0.0),
-                },
-                ..Default::default()
-            },
-        );
+    fn is_directional(&self) -> bool {
+        true
+    }
+
+    fn tick(&self, step: &mut Step, x: usize, y: usize, direction: Directions) {
+        // A generator right in front stops the mover, though one further along
+        // a pushed chain moves with it
+        let (dx, dy) = direction.offset();
+        if let Some((front_x, front_y)) = step.neighbour(x, y, dx, dy) {
+            if step.cell(front_x, front_y).id == GeneratorCell.id() {
+                return;
+            }
+        }
+        step.push(x, y, direction);
     }
 }
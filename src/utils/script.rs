@@ -0,0 +1,226 @@
+use std::{
+    cell::{Cell as Counter, RefCell},
+    fs,
+    rc::Rc,
+};
+
+use rhai::{
+    packages::{BasicArrayPackage, BasicMathPackage, CorePackage, Package},
+    CallFnOptions, Dynamic, Engine, Scope, AST,
+};
+
+use super::{cellkind, cells::Cell, directions::Directions, grid::Step};
+
+// Scripts are Rhai files defining `fn on_tick(cell)`. `cell` only sees the
+// squares right next to it and can only ask for changes, which are applied
+// with the rest of the tick:
+//
+//     cell.x, cell.y, cell.direction     position and direction (0 right, 1 down, 2 left, 3 up)
+//     cell.tick                          ticks since the level was last edited, from 0
+//     cell.neighbour(dx, dy)             id of a neighbour, -1 outside the grid
+//     cell.neighbour_direction(dx, dy)   direction of a neighbour
+//     cell.push(direction)               move this cell, pushing what is in front
+//     cell.rotate(dx, dy, clockwise)     turn a neighbour, or this cell with 0, 0
+//     cell.spawn(dx, dy, id, direction)  create a cell in an empty neighbour
+//     cell.delete(dx, dy)                remove a neighbour, or this cell with 0, 0
+//
+// The engine has no access to files, time or randomness, so the same level
+// always plays out the same way.
+
+// Operations all cells of one script may use per tick, so a runaway script
+// stalls one tick of its cells instead of the game
+const TICK_BUDGET: u64 = 100_000;
+
+#[derive(Clone)]
+enum Request {
+    Push(Directions),
+    Rotate(i64, i64, bool),
+    Spawn(i64, i64, Cell),
+    Delete(i64, i64),
+}
+
+/// The `cell` argument of `on_tick`.
+#[derive(Clone)]
+struct ScriptCell {
+    x: i64,
+    y: i64,
+    direction: i64,
+    tick: i64,
+    // Id and direction of the 3x3 squares around the cell, None outside the grid
+    around: [[Option<Cell>; 3]; 3],
+    requests: Rc<RefCell<Vec<Request>>>,
+}
+
+impl ScriptCell {
+    fn around(&self, dx: i64, dy: i64) -> Option<Cell> {
+        if dx.abs() > 1 || dy.abs() > 1 {
+            return None;
+        }
+        self.around[(dx + 1) as usize][(dy + 1) as usize]
+    }
+
+    fn request(&self, request: Request) {
+        self.requests.borrow_mut().push(request);
+    }
+}
+
+fn direction(value: i64) -> Directions {
+    Directions::from_index(value.rem_euclid(4) as u8)
+}
+
+struct Budget {
+    // Operations used by earlier calls in this tick
+    used: Counter<u64>,
+    // Operations of the call that is running
+    current: Counter<u64>,
+}
+
+/// A compiled script with its own sandboxed engine.
+pub struct Script {
+    path: String,
+    engine: Engine,
+    ast: AST,
+    budget: Rc<Budget>,
+    // First error the script ran into, it will most likely fail the same way
+    // every tick
+    error: RefCell<Option<String>>,
+}
+
+impl Script {
+    pub fn load(path: &str) -> Result<Script, String> {
+        let source = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+
+        let budget = Rc::new(Budget {
+            used: Counter::new(0),
+            current: Counter::new(0),
+        });
+
+        let mut engine = Engine::new_raw();
+        engine.register_global_module(CorePackage::new().as_shared_module());
+        engine.register_global_module(BasicMathPackage::new().as_shared_module());
+        engine.register_global_module(BasicArrayPackage::new().as_shared_module());
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        engine.set_max_call_levels(32);
+        engine.set_max_expr_depths(64, 32);
+        engine.set_max_string_size(1024);
+        engine.set_max_array_size(1024);
+        let progress = budget.clone();
+        engine.on_progress(move |operations| {
+            progress.current.set(operations);
+            if progress.used.get() + operations > TICK_BUDGET {
+                Some("Out of operations for this tick".into())
+            } else {
+                None
+            }
+        });
+
+        engine
+            .register_type_with_name::<ScriptCell>("Cell")
+            .register_get("x", |cell: &mut ScriptCell| cell.x)
+            .register_get("y", |cell: &mut ScriptCell| cell.y)
+            .register_get("direction", |cell: &mut ScriptCell| cell.direction)
+            .register_get("tick", |cell: &mut ScriptCell| cell.tick)
+            .register_fn("neighbour", |cell: &mut ScriptCell, dx: i64, dy: i64| {
+                cell.around(dx, dy).map_or(-1, |cell| cell.id as i64)
+            })
+            .register_fn("neighbour_direction", |cell: &mut ScriptCell, dx: i64, dy: i64| {
+                cell.around(dx, dy).map_or(-1, |cell| cell.direction.index() as i64)
+            })
+            .register_fn("push", |cell: &mut ScriptCell, dir: i64| cell.request(Request::Push(direction(dir))))
+            .register_fn("rotate", |cell: &mut ScriptCell, dx: i64, dy: i64, clockwise: bool| {
+                cell.request(Request::Rotate(dx, dy, clockwise))
+            })
+            .register_fn("spawn", |cell: &mut ScriptCell, dx: i64, dy: i64, id: i64, dir: i64| {
+                // Unknown ids are ignored rather than stopping the script
+                if let Ok(id) = u8::try_from(id) {
+                    if id != 0 && cellkind::get(id).is_some() {
+                        cell.request(Request::Spawn(dx, dy, Cell::new(id, direction(dir))));
+                    }
+                }
+            })
+            .register_fn("delete", |cell: &mut ScriptCell, dx: i64, dy: i64| cell.request(Request::Delete(dx, dy)));
+
+        let ast = engine.compile(&source).map_err(|err| format!("{}: {}", path, err))?;
+        if !ast.iter_functions().any(|function| function.name == "on_tick" && function.params.len() == 1) {
+            return Err(format!("{}: No on_tick(cell) function", path));
+        }
+
+        Ok(Script {
+            path: path.to_string(),
+            engine,
+            ast,
+            budget,
+            error: RefCell::new(None),
+        })
+    }
+
+    /// The first error the script ran into, if any.
+    pub fn error(&self) -> Option<String> {
+        self.error.borrow().clone()
+    }
+
+    /// Runs `on_tick` for the cell at `(x, y)` and queues what it asked for.
+    /// Nothing is queued if the script fails or runs out of operations.
+    pub fn run(&self, step: &mut Step, x: usize, y: usize, direction: Directions) {
+        let mut around = [[None; 3]; 3];
+        for (i, column) in around.iter_mut().enumerate() {
+            for (j, square) in column.iter_mut().enumerate() {
+                *square = step
+                    .neighbour(x, y, i as i32 - 1, j as i32 - 1)
+                    .map(|(nx, ny)| step.cell(nx, ny));
+            }
+        }
+        let requests = Rc::new(RefCell::new(Vec::new()));
+        let cell = ScriptCell {
+            x: x as i64,
+            y: y as i64,
+            direction: direction.index() as i64,
+            tick: step.tick() as i64,
+            around,
+            requests: requests.clone(),
+        };
+
+        self.budget.used.set(*step.script_operations(&self.path));
+        self.budget.current.set(0);
+        let options = CallFnOptions::new().eval_ast(false).rewind_scope(true);
+        let result = self
+            .engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &self.ast, "on_tick", (cell,));
+        *step.script_operations(&self.path) += self.budget.current.get();
+        if let Err(err) = result {
+            self.error.borrow_mut().get_or_insert_with(|| format!("{}: {}", self.path, err));
+            return;
+        }
+
+        for request in requests.take() {
+            let target = |dx: i64, dy: i64| {
+                if dx.abs() > 1 || dy.abs() > 1 {
+                    None
+                } else {
+                    step.neighbour(x, y, dx as i32, dy as i32)
+                }
+            };
+            match request {
+                Request::Push(direction) => {
+                    step.push(x, y, direction);
+                }
+                Request::Rotate(dx, dy, clockwise) => {
+                    if let Some((tx, ty)) = target(dx, dy) {
+                        step.rotate(tx, ty, clockwise);
+                    }
+                }
+                Request::Spawn(dx, dy, cell) => {
+                    if let Some((tx, ty)) = target(dx, dy) {
+                        step.spawn(tx, ty, (x, y), cell);
+                    }
+                }
+                Request::Delete(dx, dy) => {
+                    if let Some((tx, ty)) = target(dx, dy) {
+                        step.delete(tx, ty);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use std::{cell::RefCell, collections::HashMap, fs};
+
+use macroquad::prelude::*;
+
+// Cells only store their kind, so every cell of a kind shares one texture.
+// Textures are loaded on first use, which keeps grids usable without a window.
+thread_local! {
+    static TEXTURES: RefCell<HashMap<String, Texture2D>> = RefCell::new(HashMap::new());
+}
+
+fn load(path: &str) -> Texture2D {
+    let image = fs::read(path)
+        .ok()
+        .and_then(|bytes| Image::from_file_with_format(&bytes, None).ok())
+        // Missing textures show up bright pink instead of crashing the game
+        .unwrap_or_else(|| Image::gen_image_color(16, 16, MAGENTA));
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+pub fn get(path: &str) -> Texture2D {
+    TEXTURES.with(|textures| {
+        if let Some(texture) = textures.borrow().get(path) {
+            return texture.clone();
+        }
+        let texture = load(path);
+        textures.borrow_mut().insert(path.to_string(), texture.clone());
+        texture
+    })
+}
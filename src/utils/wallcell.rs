@@ -0,0 +1,26 @@
+use super::{cellkind::CellKind, directions::Side};
+
+/// Never moves and can't be pushed or copied.
+pub struct WallCell;
+
+impl CellKind for WallCell {
+    fn id(&self) -> u8 {
+        4
+    }
+
+    fn name(&self) -> &str {
+        "Wall"
+    }
+
+    fn texture(&self) -> &str {
+        "assets/wallcell.png"
+    }
+
+    fn is_pushable(&self, _side: Side) -> bool {
+        false
+    }
+
+    fn is_generatable(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,55 @@
+use macroquad::prelude::*;
+
+use rcmmm::Event;
+
+const WIDTH: f32 = 420.0;
+const LINE_HEIGHT: f32 = 24.0;
+const PADDING: f32 = 12.0;
+const MAX_LINES: usize = 30;
+
+/// Panel on the right side of the screen listing what happened in the last
+/// tick, so a paused machine can be inspected step by step.
+pub struct EventLog {
+    pub visible: bool,
+}
+
+impl EventLog {
+    pub fn new() -> EventLog {
+        EventLog { visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    fn bounds(&self, events: &[Event]) -> Rect {
+        let shown = events.len().min(MAX_LINES);
+        let lines = shown + 1 + usize::from(events.len() > shown);
+        let height = lines as f32 * LINE_HEIGHT + PADDING * 2.0;
+        Rect::new(screen_width() - WIDTH - PADDING, PADDING, WIDTH, height)
+    }
+
+    /// Whether the point is over the log, so clicks there don't reach the grid.
+    pub fn contains(&self, events: &[Event], point: (f32, f32)) -> bool {
+        self.visible && self.bounds(events).contains(vec2(point.0, point.1))
+    }
+
+    pub fn draw(&self, tick: u64, events: &[Event]) {
+        if !self.visible {
+            return;
+        }
+        let shown = events.len().min(MAX_LINES);
+        let Rect { x, y, w, h } = self.bounds(events);
+        draw_rectangle(x, y, w, h, Color::new(0.08, 0.08, 0.08, 0.85));
+
+        let text_x = x + PADDING;
+        let line_y = |i: usize| y + PADDING + (i + 1) as f32 * LINE_HEIGHT - 6.0;
+        draw_text(&format!("Tick {}: {} events", tick, events.len()), text_x, line_y(0), 24.0, WHITE);
+        for (i, event) in events.iter().take(shown).enumerate() {
+            draw_text(&event.to_string(), text_x, line_y(i + 1), 20.0, LIGHTGRAY);
+        }
+        if events.len() > shown {
+            draw_text(&format!("and {} more", events.len() - shown), text_x, line_y(shown + 1), 20.0, GRAY);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use macroquad::prelude::*;
+
+use rcmmm::Grid;
+
+use crate::app::heatmap::{Heatmap, HeatmapMode, WINDOW};
+
+const LEGEND_WIDTH: f32 = 240.0;
+const LEGEND_HEIGHT: f32 = 16.0;
+const PADDING: f32 = 12.0;
+// Squares that never counted are dimmed so the busy ones stand out
+const DEAD_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.5);
+
+const COLD: Color = Color::new(0.0, 0.4, 1.0, 1.0);
+const WARM: Color = Color::new(1.0, 0.9, 0.0, 1.0);
+const HOT: Color = Color::new(1.0, 0.1, 0.0, 1.0);
+
+fn mix(from: Color, to: Color, t: f32) -> Color {
+    Color::new(
+        from.r + (to.r - from.r) * t,
+        from.g + (to.g - from.g) * t,
+        from.b + (to.b - from.b) * t,
+        from.a + (to.a - from.a) * t,
+    )
+}
+
+// Blue for rarely, through yellow, to red for every tick
+fn heat_color(heat: f32) -> Color {
+    if heat < 0.5 {
+        mix(COLD, WARM, heat * 2.0)
+    } else {
+        mix(WARM, HOT, heat * 2.0 - 1.0)
+    }
+}
+
+/// Colours the squares of the grid by how often they changed or were
+/// occupied in the recent ticks.
+pub struct HeatmapOverlay {
+    // Hidden when there is no mode
+    pub mode: Option<HeatmapMode>,
+}
+
+impl HeatmapOverlay {
+    pub fn new() -> HeatmapOverlay {
+        HeatmapOverlay { mode: None }
+    }
+
+    /// Goes from hidden to activity to occupancy and back to hidden.
+    pub fn toggle(&mut self) {
+        self.mode = match self.mode {
+            None => Some(HeatmapMode::Activity),
+            Some(HeatmapMode::Activity) => Some(HeatmapMode::Occupancy),
+            Some(HeatmapMode::Occupancy) => None,
+        };
+    }
+
+    pub fn draw(&self, grid: &Grid, heatmap: &Heatmap) {
+        let Some(mode) = self.mode else {
+            return;
+        };
+        let ticks = heatmap.ticks().max(1) as f32;
+        for x in 0..grid.width() as usize {
+            for y in 0..grid.height() as usize {
+                let draw_x = x as f32 * 64.0 + grid.draw_offset.0 as f32;
+                let draw_y = y as f32 * 64.0 + grid.draw_offset.1 as f32;
+                if draw_x < -64.0 || draw_x > screen_width() + 64.0 || draw_y < -64.0 || draw_y > screen_height() + 64.0 {
+                    continue;
+                }
+                let count = heatmap.count(mode, x, y);
+                let color = if count == 0 {
+                    DEAD_COLOR
+                } else {
+                    let color = heat_color(count as f32 / ticks);
+                    Color::new(color.r, color.g, color.b, 0.55)
+                };
+                draw_rectangle(draw_x, draw_y, 64.0, 64.0, color);
+            }
+        }
+
+        // Legend in the bottom right corner
+        let x = screen_width() - LEGEND_WIDTH - PADDING;
+        let y = screen_height() - LEGEND_HEIGHT - PADDING * 2.0 - 24.0;
+        draw_rectangle(
+            x - PADDING,
+            y - PADDING - 24.0,
+            LEGEND_WIDTH + PADDING * 2.0,
+            LEGEND_HEIGHT + PADDING * 2.0 + 48.0,
+            Color::new(0.08, 0.08, 0.08, 0.85),
+        );
+        draw_text(
+            &format!("{} over {} of {} ticks", mode.name(), heatmap.ticks(), WINDOW),
+            x,
+            y - 8.0,
+            20.0,
+            WHITE,
+        );
+        let steps = 48;
+        let step_width = LEGEND_WIDTH / steps as f32;
+        for i in 0..steps {
+            let color = heat_color((i + 1) as f32 / steps as f32);
+            draw_rectangle(x + i as f32 * step_width, y, step_width, LEGEND_HEIGHT, color);
+        }
+        draw_text("Rarely", x, y + LEGEND_HEIGHT + 20.0, 20.0, LIGHTGRAY);
+        let every = "Every tick";
+        let every_width = measure_text(every, None, 20, 1.0).width;
+        draw_text(every, x + LEGEND_WIDTH - every_width, y + LEGEND_HEIGHT + 20.0, 20.0, LIGHTGRAY);
+    }
+}
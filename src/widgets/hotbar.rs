@@ -0,0 +1,104 @@
+use macroquad::prelude::*;
+
+use rcmmm::{Cell, Directions};
+
+use crate::app::controls::{Action, Bindings};
+
+const SLOT_SIZE: f32 = 64.0;
+const SLOT_PADDING: f32 = 8.0;
+
+pub struct Hotbar {
+    pub slots: Vec<Cell>,
+    pub selected: usize,
+}
+
+impl Hotbar {
+    pub fn new() -> Hotbar {
+        Hotbar {
+            slots: Cell::placeable(),
+            selected: 0,
+        }
+    }
+
+    pub fn selected_cell(&self) -> &Cell {
+        &self.slots[self.selected]
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.slots.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.slots.len() - 1) % self.slots.len();
+    }
+
+    fn bounds(&self) -> Rect {
+        let width = self.slots.len() as f32 * (SLOT_SIZE + SLOT_PADDING) + SLOT_PADDING;
+        let height = SLOT_SIZE + SLOT_PADDING * 2.0;
+        Rect::new(
+            (screen_width() - width) / 2.0,
+            screen_height() - height - SLOT_PADDING,
+            width,
+            height,
+        )
+    }
+
+    fn slot_rect(&self, index: usize) -> Rect {
+        let bounds = self.bounds();
+        Rect::new(
+            bounds.x + SLOT_PADDING + index as f32 * (SLOT_SIZE + SLOT_PADDING),
+            bounds.y + SLOT_PADDING,
+            SLOT_SIZE,
+            SLOT_SIZE,
+        )
+    }
+
+    /// Whether the point is over the hotbar, so clicks there don't reach the grid.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        self.bounds().contains(vec2(point.0, point.1))
+    }
+
+    pub fn update(&mut self, bindings: &Bindings) {
+        for (i, slot) in Action::HOTBAR_SLOTS.iter().enumerate().take(self.slots.len()) {
+            if bindings.pressed(*slot) {
+                self.selected = i;
+            }
+        }
+
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let mouse_pos = mouse_position();
+            for i in 0..self.slots.len() {
+                if self.slot_rect(i).contains(vec2(mouse_pos.0, mouse_pos.1)) {
+                    self.selected = i;
+                }
+            }
+        }
+    }
+
+    pub fn draw(&self, place_direction: &Directions, bindings: &Bindings) {
+        let bounds = self.bounds();
+        draw_rectangle(bounds.x, bounds.y, bounds.w, bounds.h, Color::new(0.0, 0.0, 0.0, 0.75));
+
+        for (i, cell) in self.slots.iter().enumerate() {
+            let rect = self.slot_rect(i);
+            let cell = Cell::new(cell.id, *place_direction);
+            cell.draw(rect.x, rect.y, cell.rotation());
+
+            if i == self.selected {
+                draw_rectangle_lines(rect.x - 2.0, rect.y - 2.0, rect.w + 4.0, rect.h + 4.0, 4.0, YELLOW);
+            }
+
+            if let Some(slot) = Action::HOTBAR_SLOTS.get(i) {
+                draw_text(&bindings.get(*slot).label(), rect.x + 4.0, rect.y + 18.0, 24.0, WHITE);
+            }
+        }
+
+        draw_text(
+            self.selected_cell().kind().name(),
+            bounds.x,
+            bounds.y - 8.0,
+            32.0,
+            WHITE,
+        );
+    }
+}
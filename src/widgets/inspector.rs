@@ -0,0 +1,87 @@
+use macroquad::prelude::*;
+
+use rcmmm::{Grid, Side};
+
+const WIDTH: f32 = 420.0;
+const LINE_HEIGHT: f32 = 28.0;
+const PADDING: f32 = 12.0;
+
+/// Panel in the bottom left corner describing the square under the mouse.
+pub struct Inspector {
+    pub visible: bool,
+}
+
+impl Inspector {
+    pub fn new() -> Inspector {
+        Inspector { visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Marks the inspected square on the grid, or clears the mark when hidden.
+    pub fn update(&self, grid: &mut Grid, square: Option<(u32, u32)>) {
+        grid.highlighted_cell = if self.visible { square } else { None };
+    }
+
+    // What to show about the highlighted square, or None when there is none
+    fn lines(&self, grid: &Grid) -> Option<Vec<String>> {
+        let (x, y) = grid.highlighted_cell.filter(|_| self.visible)?;
+        let cell = grid.cell(x, y)?;
+
+        let mut lines = vec![format!("Square {},{}", x, y)];
+        if cell.is_empty() {
+            lines.push("Empty".to_string());
+        } else {
+            let kind = cell.kind();
+            lines.push(format!("{} (id {})", kind.name(), kind.id()));
+            if kind.is_directional() {
+                lines.push(format!("Facing {:?}", cell.direction));
+            }
+            let sides: Vec<String> = [Side::Front, Side::Back, Side::Left, Side::Right]
+                .into_iter()
+                .filter(|side| kind.is_pushable(*side))
+                .map(|side| format!("{:?}", side).to_lowercase())
+                .collect();
+            lines.push(match sides.len() {
+                0 => "Not pushable".to_string(),
+                4 => "Pushable from all sides".to_string(),
+                _ => format!("Pushable from {}", sides.join(", ")),
+            });
+        }
+        let events: Vec<String> = grid
+            .events
+            .iter()
+            .filter(|event| event.square() == (x as usize, y as usize))
+            .map(|event| event.to_string())
+            .collect();
+        if events.is_empty() {
+            lines.push("Nothing happened here last tick".to_string());
+        }
+        lines.extend(events);
+        Some(lines)
+    }
+
+    fn bounds(lines: &[String]) -> Rect {
+        let height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0;
+        Rect::new(PADDING, screen_height() - height - PADDING, WIDTH, height)
+    }
+
+    /// Whether the point is over the panel, so clicks there don't reach the grid.
+    pub fn contains(&self, grid: &Grid, point: (f32, f32)) -> bool {
+        self.lines(grid).is_some_and(|lines| Self::bounds(&lines).contains(vec2(point.0, point.1)))
+    }
+
+    pub fn draw(&self, grid: &Grid) {
+        let Some(lines) = self.lines(grid) else {
+            return;
+        };
+        let bounds = Self::bounds(&lines);
+        draw_rectangle(bounds.x, bounds.y, bounds.w, bounds.h, Color::new(0.08, 0.08, 0.08, 0.85));
+        for (i, line) in lines.iter().enumerate() {
+            let color = if i == 0 { WHITE } else { LIGHTGRAY };
+            draw_text(line, PADDING * 2.0, bounds.y + PADDING + (i + 1) as f32 * LINE_HEIGHT - 8.0, 24.0, color);
+        }
+    }
+}
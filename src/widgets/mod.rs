@@ -1 +1,9 @@
+pub mod eventlog;
 pub mod fpsviewer;
+pub mod heatmapoverlay;
+pub mod hotbar;
+pub mod inspector;
+pub mod statsviewer;
+pub mod timelinebar;
+pub mod trailrenderer;
+pub mod ui;
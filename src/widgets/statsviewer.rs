@@ -0,0 +1,98 @@
+use macroquad::prelude::*;
+
+use rcmmm::cellkind;
+
+use crate::app::stats::{Stats, TickStats};
+
+const WIDTH: f32 = 360.0;
+const GRAPH_HEIGHT: f32 = 96.0;
+const LEGEND_HEIGHT: f32 = 22.0;
+const PADDING: f32 = 8.0;
+// Ticks shown, the newest on the right
+const SHOWN_TICKS: usize = 120;
+const PALETTE: [Color; 8] = [SKYBLUE, YELLOW, GREEN, LIGHTGRAY, PINK, ORANGE, VIOLET, BEIGE];
+
+// One line of a graph, with a value per shown tick
+struct Series {
+    name: String,
+    color: Color,
+    values: Vec<usize>,
+}
+
+/// Line graphs of the cell counts and of the moves, generated and destroyed
+/// cells of the last ticks.
+pub struct StatsViewer {
+    pub position: (f32, f32),
+    pub visible: bool,
+}
+
+impl StatsViewer {
+    pub fn new(x: f32, y: f32) -> StatsViewer {
+        StatsViewer { position: (x, y), visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Whether the point is over the graphs while they are shown, so clicks
+    /// there don't reach the grid.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        let height = (GRAPH_HEIGHT + LEGEND_HEIGHT) * 2.0 + PADDING;
+        let bounds = Rect::new(self.position.0, self.position.1, WIDTH, height);
+        self.visible && bounds.contains(vec2(point.0, point.1))
+    }
+
+    // Draws one line per series over the shown ticks, scaled to the largest value
+    fn draw_graph(&self, y: f32, series: &[Series]) {
+        let x = self.position.0;
+        draw_rectangle(x, y, WIDTH, GRAPH_HEIGHT, Color::new(0.0, 0.0, 0.0, 0.6));
+        let max = series.iter().flat_map(|line| line.values.iter().copied()).max().unwrap_or(0).max(1);
+        let step = WIDTH / (SHOWN_TICKS - 1) as f32;
+        let point = |i: usize, value: usize| (x + i as f32 * step, y + GRAPH_HEIGHT - value as f32 / max as f32 * GRAPH_HEIGHT);
+
+        for line in series {
+            for (i, pair) in line.values.windows(2).enumerate() {
+                let (x1, y1) = point(i, pair[0]);
+                let (x2, y2) = point(i + 1, pair[1]);
+                draw_line(x1, y1, x2, y2, 2.0, line.color);
+            }
+        }
+        draw_text(&max.to_string(), x + WIDTH + 4.0, y + 16.0, 20.0, GRAY);
+
+        let mut legend_x = x;
+        for line in series {
+            let label = format!("{}: {}", line.name, line.values.last().copied().unwrap_or(0));
+            draw_text(&label, legend_x, y + GRAPH_HEIGHT + LEGEND_HEIGHT - 6.0, 20.0, line.color);
+            legend_x += measure_text(&label, None, 20, 1.0).width + 12.0;
+        }
+    }
+
+    pub fn draw(&self, stats: &Stats) {
+        if !self.visible {
+            return;
+        }
+        let all = stats.ticks();
+        let ticks: Vec<&TickStats> = all.iter().skip(all.len().saturating_sub(SHOWN_TICKS)).collect();
+        let series = |name: &str, color: Color, value: &dyn Fn(&TickStats) -> usize| Series {
+            name: name.to_string(),
+            color,
+            values: ticks.iter().map(|stats| value(stats)).collect(),
+        };
+
+        let counts: Vec<Series> = cellkind::all()
+            .iter()
+            .filter(|kind| kind.id() != 0)
+            .enumerate()
+            .map(|(i, kind)| series(kind.name(), PALETTE[i % PALETTE.len()], &|stats| stats.count(kind.id())))
+            .collect();
+        let activity = [
+            series("Moves", SKYBLUE, &|stats| stats.moves),
+            series("Generated", GREEN, &|stats| stats.generated),
+            series("Destroyed", RED, &|stats| stats.destroyed),
+        ];
+
+        self.draw_graph(self.position.1, &counts);
+        self.draw_graph(self.position.1 + GRAPH_HEIGHT + LEGEND_HEIGHT + PADDING, &activity);
+    }
+}
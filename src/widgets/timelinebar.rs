@@ -0,0 +1,55 @@
+use macroquad::prelude::*;
+
+const WIDTH: f32 = 480.0;
+const HEIGHT: f32 = 32.0;
+const MARGIN: f32 = 16.0;
+
+/// Slider at the top of the screen for picking a tick of the timeline.
+pub struct TimelineBar {
+    dragging: bool,
+}
+
+impl TimelineBar {
+    pub fn new() -> TimelineBar {
+        TimelineBar { dragging: false }
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::new((screen_width() - WIDTH) / 2.0, MARGIN, WIDTH, HEIGHT)
+    }
+
+    /// Whether the point is over the bar, so clicks there don't reach the grid.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        self.bounds().contains(vec2(point.0, point.1))
+    }
+
+    /// The tick under the mouse while the bar is being dragged.
+    pub fn update(&mut self, first: u64, last: u64) -> Option<u64> {
+        let mouse_pos = mouse_position();
+        if is_mouse_button_pressed(MouseButton::Left) && self.contains(mouse_pos) {
+            self.dragging = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if !self.dragging {
+            return None;
+        }
+
+        let bounds = self.bounds();
+        let t = ((mouse_pos.0 - bounds.x) / bounds.w).clamp(0.0, 1.0);
+        Some(first + ((last - first) as f32 * t).round() as u64)
+    }
+
+    pub fn draw(&self, first: u64, last: u64, shown: u64) {
+        let bounds = self.bounds();
+        draw_rectangle(bounds.x, bounds.y, bounds.w, bounds.h, Color::new(0.0, 0.0, 0.0, 0.75));
+
+        let t = if last > first { (shown - first) as f32 / (last - first) as f32 } else { 1.0 };
+        draw_rectangle(bounds.x, bounds.y, bounds.w * t, bounds.h, Color::new(1.0, 1.0, 1.0, 0.2));
+        draw_rectangle(bounds.x + bounds.w * t - 2.0, bounds.y, 4.0, bounds.h, YELLOW);
+
+        let label = format!("Tick {} of {}..{}", shown, first, last);
+        draw_text(&label, bounds.x + 8.0, bounds.y + bounds.h - 8.0, 24.0, WHITE);
+    }
+}
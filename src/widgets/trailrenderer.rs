@@ -0,0 +1,48 @@
+use macroquad::prelude::*;
+
+use rcmmm::Grid;
+
+use crate::app::trails::{Trails, LENGTH};
+
+const PALETTE: [Color; 6] = [ORANGE, SKYBLUE, LIME, PINK, YELLOW, VIOLET];
+
+/// Draws fading lines along the squares moving cells passed through.
+pub struct TrailRenderer {
+    pub visible: bool,
+}
+
+impl TrailRenderer {
+    pub fn new() -> TrailRenderer {
+        TrailRenderer { visible: false }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn draw(&self, grid: &Grid, trails: &Trails, tick: u64) {
+        if !self.visible {
+            return;
+        }
+        let centre = |(x, y): (usize, usize)| {
+            (
+                x as f32 * 64.0 + 32.0 + grid.draw_offset.0 as f32,
+                y as f32 * 64.0 + 32.0 + grid.draw_offset.1 as f32,
+            )
+        };
+        for (identity, trail) in trails.trails() {
+            let color = PALETTE[identity as usize % PALETTE.len()];
+            for (&(_, from), &(reached, to)) in trail.iter().zip(trail.iter().skip(1)) {
+                // Steps over a wrapping edge would cross the whole grid
+                if from.0.abs_diff(to.0) > 1 || from.1.abs_diff(to.1) > 1 {
+                    continue;
+                }
+                let age = tick.saturating_sub(reached) as f32;
+                let alpha = (1.0 - age / LENGTH as f32).clamp(0.0, 1.0) * 0.8;
+                let (x1, y1) = centre(from);
+                let (x2, y2) = centre(to);
+                draw_line(x1, y1, x2, y2, 6.0, Color::new(color.r, color.g, color.b, alpha));
+            }
+        }
+    }
+}
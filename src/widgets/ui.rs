@@ -0,0 +1,420 @@
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use macroquad::prelude::*;
+
+use crate::app::controls::{Action, Bindings};
+
+pub const TEXT_SIZE: f32 = 32.0;
+pub const ROW_HEIGHT: f32 = 40.0;
+
+const PANEL_COLOR: Color = Color::new(0.08, 0.08, 0.08, 0.92);
+const TITLE_COLOR: Color = Color::new(0.16, 0.16, 0.16, 1.0);
+const IDLE_COLOR: Color = Color::new(0.2, 0.2, 0.2, 1.0);
+const HOVER_COLOR: Color = Color::new(0.3, 0.3, 0.3, 1.0);
+const ACTIVE_COLOR: Color = Color::new(0.45, 0.45, 0.45, 1.0);
+const FILL_COLOR: Color = Color::new(0.85, 0.75, 0.1, 1.0);
+const FOCUS_COLOR: Color = YELLOW;
+
+enum DrawCommand {
+    Rectangle(Rect, Color),
+    RectangleLines(Rect, f32, Color),
+    Text(String, f32, f32, f32, Color),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WidgetKind {
+    Control,
+    TextInput,
+    // Lists use the arrow keys themselves, so they don't move focus with them
+    List,
+}
+
+struct Focusable {
+    id: u64,
+    kind: WidgetKind,
+}
+
+fn widget_id(id: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Immediate-mode widget set.
+///
+/// Call `begin` at the start of a screen's `update`, then the widget functions,
+/// which return what the user did this frame, then `end`. The widgets are
+/// queued rather than drawn immediately, so `draw` has to be called from the
+/// screen's `draw` after the background is cleared.
+///
+/// Focus moves with Tab / Shift+Tab or the menu Up and Down keys, Confirm
+/// activates the focused widget and clicking a widget focuses it. Tab and the
+/// text editing keys are fixed, everything else comes from the key bindings
+/// passed to `begin`.
+pub struct Ui {
+    hot: Option<u64>,
+    active: Option<u64>,
+    focused: Option<u64>,
+    focus_order: Vec<Focusable>,
+    previous_focus_order: Vec<Focusable>,
+    chars: Vec<char>,
+    scroll: HashMap<u64, usize>,
+    commands: Vec<DrawCommand>,
+    bindings: Bindings,
+}
+
+impl Ui {
+    pub fn new() -> Ui {
+        Ui {
+            hot: None,
+            active: None,
+            focused: None,
+            focus_order: Vec::new(),
+            previous_focus_order: Vec::new(),
+            chars: Vec::new(),
+            scroll: HashMap::new(),
+            commands: Vec::new(),
+            bindings: Bindings::default(),
+        }
+    }
+
+    pub fn begin(&mut self, bindings: &Bindings) {
+        self.bindings = bindings.clone();
+        self.commands.clear();
+        self.hot = None;
+        self.previous_focus_order = std::mem::take(&mut self.focus_order);
+
+        self.chars.clear();
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() {
+                self.chars.push(c);
+            }
+        }
+
+        let focused_uses_arrows = self.focused_kind() == Some(WidgetKind::List);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        if is_key_pressed(KeyCode::Tab) {
+            self.move_focus(if shift { -1 } else { 1 });
+        } else if !focused_uses_arrows {
+            if self.pressed(Action::MenuDown) {
+                self.move_focus(1);
+            } else if self.pressed(Action::MenuUp) {
+                self.move_focus(-1);
+            }
+        }
+    }
+
+    pub fn end(&mut self) {
+        if is_mouse_button_released(MouseButton::Left) {
+            self.active = None;
+        }
+        if is_mouse_button_pressed(MouseButton::Left) && self.hot.is_none() {
+            self.focused = None;
+        }
+    }
+
+    pub fn draw(&self) {
+        for command in &self.commands {
+            match command {
+                DrawCommand::Rectangle(rect, color) => draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color),
+                DrawCommand::RectangleLines(rect, thickness, color) => {
+                    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, *thickness, *color)
+                }
+                DrawCommand::Text(text, x, y, size, color) => {
+                    draw_text(text, *x, *y, *size, *color);
+                }
+            }
+        }
+    }
+
+    fn move_focus(&mut self, step: i32) {
+        let count = self.previous_focus_order.len() as i32;
+        if count == 0 {
+            return;
+        }
+        let current = self
+            .previous_focus_order
+            .iter()
+            .position(|f| Some(f.id) == self.focused);
+        let next = match current {
+            Some(i) => (i as i32 + step).rem_euclid(count),
+            None if step > 0 => 0,
+            None => count - 1,
+        };
+        self.focused = Some(self.previous_focus_order[next as usize].id);
+    }
+
+    fn focused_kind(&self) -> Option<WidgetKind> {
+        self.focus_order
+            .iter()
+            .chain(self.previous_focus_order.iter())
+            .find(|f| Some(f.id) == self.focused)
+            .map(|f| f.kind)
+    }
+
+    /// Gives keyboard focus to the widget with the given id.
+    pub fn set_focus(&mut self, id: &str) {
+        self.focused = Some(widget_id(id));
+    }
+
+    pub fn has_focus(&self, id: &str) -> bool {
+        self.focused == Some(widget_id(id))
+    }
+
+    /// Whether a text field has focus, in which case screens should ignore
+    /// their own keyboard shortcuts.
+    pub fn wants_keyboard(&self) -> bool {
+        self.focused_kind() == Some(WidgetKind::TextInput)
+    }
+
+    /// Whether the key bound to `action` was pressed this frame. Keys that type
+    /// text are ignored while a text field has focus.
+    pub fn pressed(&self, action: Action) -> bool {
+        let binding = self.bindings.get(action);
+        binding.pressed() && !(self.wants_keyboard() && binding.types_text())
+    }
+
+    fn register(&mut self, id: &str, rect: Rect, kind: WidgetKind) -> u64 {
+        let id = widget_id(id);
+        self.focus_order.push(Focusable { id, kind });
+
+        let mouse = mouse_position();
+        if rect.contains(vec2(mouse.0, mouse.1)) {
+            self.hot = Some(id);
+            if is_mouse_button_pressed(MouseButton::Left) {
+                self.active = Some(id);
+                self.focused = Some(id);
+            }
+        }
+        id
+    }
+
+    fn is_focused(&self, id: u64) -> bool {
+        self.focused == Some(id)
+    }
+
+    fn background(&self, id: u64) -> Color {
+        if self.active == Some(id) {
+            ACTIVE_COLOR
+        } else if self.hot == Some(id) {
+            HOVER_COLOR
+        } else {
+            IDLE_COLOR
+        }
+    }
+
+    fn clicked(&self, id: u64) -> bool {
+        self.active == Some(id) && self.hot == Some(id) && is_mouse_button_released(MouseButton::Left)
+    }
+
+    fn activated(&self, id: u64) -> bool {
+        self.is_focused(id) && self.pressed(Action::MenuConfirm)
+    }
+
+    fn frame(&mut self, id: u64, rect: Rect, color: Color) {
+        self.commands.push(DrawCommand::Rectangle(rect, color));
+        if self.is_focused(id) {
+            self.commands.push(DrawCommand::RectangleLines(rect, 3.0, FOCUS_COLOR));
+        }
+    }
+
+    fn centered_text(&mut self, text: &str, rect: Rect, color: Color) {
+        let size = measure_text(text, None, TEXT_SIZE as u16, 1.0);
+        self.commands.push(DrawCommand::Text(
+            text.to_string(),
+            rect.x + (rect.w - size.width) / 2.0,
+            rect.y + (rect.h + size.height) / 2.0,
+            TEXT_SIZE,
+            color,
+        ));
+    }
+
+    /// Plain text, with `y` as the baseline like `draw_text`.
+    pub fn label(&mut self, text: &str, x: f32, y: f32, size: f32, color: Color) {
+        self.commands.push(DrawCommand::Text(text.to_string(), x, y, size, color));
+    }
+
+    /// A background with a title bar. Draw it before the widgets inside it.
+    pub fn panel(&mut self, rect: Rect, title: &str) {
+        self.commands.push(DrawCommand::Rectangle(rect, PANEL_COLOR));
+        self.commands.push(DrawCommand::Rectangle(Rect::new(rect.x, rect.y, rect.w, ROW_HEIGHT), TITLE_COLOR));
+        self.commands.push(DrawCommand::Text(title.to_string(), rect.x + 12.0, rect.y + 28.0, TEXT_SIZE, WHITE));
+    }
+
+    /// Returns true on the frame the button is clicked or activated with Enter.
+    pub fn button(&mut self, id: &str, rect: Rect, label: &str) -> bool {
+        let id = self.register(id, rect, WidgetKind::Control);
+        let pressed = self.clicked(id) || self.activated(id);
+
+        let color = self.background(id);
+        self.frame(id, rect, color);
+        self.centered_text(label, rect, WHITE);
+        pressed
+    }
+
+    /// A checkbox style toggle. Returns true when `value` changed.
+    pub fn toggle(&mut self, id: &str, rect: Rect, label: &str, value: &mut bool) -> bool {
+        let id = self.register(id, rect, WidgetKind::Control);
+        let changed = self.clicked(id) || self.activated(id);
+        if changed {
+            *value = !*value;
+        }
+
+        let color = self.background(id);
+        self.frame(id, rect, color);
+        let check = Rect::new(rect.x + 8.0, rect.y + (rect.h - 24.0) / 2.0, 24.0, 24.0);
+        self.commands.push(DrawCommand::RectangleLines(check, 2.0, WHITE));
+        if *value {
+            self.commands.push(DrawCommand::Rectangle(
+                Rect::new(check.x + 5.0, check.y + 5.0, check.w - 10.0, check.h - 10.0),
+                FILL_COLOR,
+            ));
+        }
+        self.commands.push(DrawCommand::Text(
+            label.to_string(),
+            check.x + check.w + 12.0,
+            rect.y + (rect.h + 20.0) / 2.0,
+            TEXT_SIZE,
+            WHITE,
+        ));
+        changed
+    }
+
+    /// A horizontal slider between `min` and `max`. Drag with the mouse or use
+    /// Left/Right while focused. Returns true when `value` changed.
+    pub fn slider(&mut self, id: &str, rect: Rect, label: &str, value: &mut f32, min: f32, max: f32) -> bool {
+        let id = self.register(id, rect, WidgetKind::Control);
+        let old_value = *value;
+
+        if self.active == Some(id) {
+            let t = ((mouse_position().0 - rect.x) / rect.w).clamp(0.0, 1.0);
+            *value = min + t * (max - min);
+        }
+        if self.is_focused(id) {
+            let step = (max - min) / 20.0;
+            if self.pressed(Action::MenuLeft) {
+                *value = (*value - step).max(min);
+            }
+            if self.pressed(Action::MenuRight) {
+                *value = (*value + step).min(max);
+            }
+        }
+
+        let color = self.background(id);
+        self.frame(id, rect, color);
+        let t = ((*value - min) / (max - min)).clamp(0.0, 1.0);
+        self.commands.push(DrawCommand::Rectangle(
+            Rect::new(rect.x, rect.y + rect.h - 6.0, rect.w * t, 6.0),
+            FILL_COLOR,
+        ));
+        self.centered_text(&format!("{}: {:.1}", label, value), rect, WHITE);
+        *value != old_value
+    }
+
+    /// A single line text field holding at most `max_len` characters.
+    /// Returns true when `text` changed.
+    pub fn text_input(&mut self, id: &str, rect: Rect, text: &mut String, max_len: usize) -> bool {
+        let id = self.register(id, rect, WidgetKind::TextInput);
+        let mut changed = false;
+
+        if self.is_focused(id) {
+            for c in &self.chars {
+                if text.chars().count() < max_len {
+                    text.push(*c);
+                    changed = true;
+                }
+            }
+            if is_key_pressed(KeyCode::Backspace) && text.pop().is_some() {
+                changed = true;
+            }
+        }
+
+        self.frame(id, rect, IDLE_COLOR);
+        let mut shown = text.clone();
+        if self.is_focused(id) && get_time() % 1.0 < 0.5 {
+            shown.push('|');
+        }
+        while shown.chars().count() > 1 && measure_text(&shown, None, TEXT_SIZE as u16, 1.0).width > rect.w - 16.0 {
+            shown.remove(0);
+        }
+        self.commands.push(DrawCommand::Text(shown, rect.x + 8.0, rect.y + (rect.h + 20.0) / 2.0, TEXT_SIZE, WHITE));
+        changed
+    }
+
+    /// A scrollable list of items. Scroll with the mouse wheel and pick with a
+    /// click, or use Up/Down while focused. Returns true when `selected` changed.
+    pub fn list(&mut self, id: &str, rect: Rect, items: &[String], selected: &mut Option<usize>) -> bool {
+        let id = self.register(id, rect, WidgetKind::List);
+        let old_selected = *selected;
+        let visible_rows = ((rect.h / ROW_HEIGHT) as usize).max(1);
+        let max_scroll = items.len().saturating_sub(visible_rows);
+        let mut scroll = self.scroll.get(&id).copied().unwrap_or(0).min(max_scroll);
+
+        if self.hot == Some(id) {
+            let wheel = mouse_wheel().1;
+            if wheel > 0.0 {
+                scroll = scroll.saturating_sub(1);
+            } else if wheel < 0.0 {
+                scroll = (scroll + 1).min(max_scroll);
+            }
+            if is_mouse_button_pressed(MouseButton::Left) {
+                let row = ((mouse_position().1 - rect.y) / ROW_HEIGHT) as usize + scroll;
+                if row < items.len() {
+                    *selected = Some(row);
+                }
+            }
+        }
+
+        if self.is_focused(id) && !items.is_empty() {
+            if self.pressed(Action::MenuDown) {
+                *selected = Some(selected.map_or(0, |i| (i + 1).min(items.len() - 1)));
+            }
+            if self.pressed(Action::MenuUp) {
+                *selected = Some(selected.map_or(0, |i| i.saturating_sub(1)));
+            }
+        }
+
+        // Keep keyboard selection in view
+        if *selected != old_selected {
+            if let Some(i) = *selected {
+                if i < scroll {
+                    scroll = i;
+                } else if i >= scroll + visible_rows {
+                    scroll = i + 1 - visible_rows;
+                }
+            }
+        }
+        self.scroll.insert(id, scroll);
+
+        self.frame(id, rect, IDLE_COLOR);
+        for (row, item) in items.iter().enumerate().skip(scroll).take(visible_rows) {
+            let row_rect = Rect::new(rect.x, rect.y + (row - scroll) as f32 * ROW_HEIGHT, rect.w, ROW_HEIGHT);
+            if Some(row) == *selected {
+                self.commands.push(DrawCommand::Rectangle(row_rect, ACTIVE_COLOR));
+            }
+            self.commands.push(DrawCommand::Text(
+                item.clone(),
+                row_rect.x + 8.0,
+                row_rect.y + 28.0,
+                TEXT_SIZE,
+                WHITE,
+            ));
+        }
+        if max_scroll > 0 {
+            let bar_height = rect.h * visible_rows as f32 / items.len() as f32;
+            let bar_y = rect.y + (rect.h - bar_height) * scroll as f32 / max_scroll as f32;
+            self.commands.push(DrawCommand::Rectangle(Rect::new(rect.x + rect.w - 6.0, bar_y, 6.0, bar_height), FILL_COLOR));
+        }
+
+        *selected != old_selected
+    }
+}
+
+impl Default for Ui {
+    fn default() -> Self {
+        Ui::new()
+    }
+}
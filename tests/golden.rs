@@ -0,0 +1,101 @@
+// Golden-file tests for the grid mechanics. Every file in tests/golden is one
+// case: a few `key = value` settings, the starting grid, a `---` line and the
+// grid expected after stepping, e.g.
+//
+//     // A mover pushes a push cell along
+//     ticks = 2
+//     > # . .
+//     ---
+//     . . > #
+//
+// `ticks` is required and `wrap = true` makes the edges wrap. Lines starting
+// with `//` are comments, since `#` is a push cell. Grids are written as
+// described in `asciigrid`.
+//
+// Run `BLESS=1 cargo test --test golden` to overwrite the expected grids with
+// what the simulation currently does, then review the diff.
+
+use std::{env, fs, path::Path};
+
+use rcmmm::{asciigrid, Grid};
+
+const CASES_DIR: &str = "tests/golden";
+const SEPARATOR: &str = "---";
+
+struct Case {
+    // Everything up to and including the separator, kept as written for blessing
+    head: String,
+    grid: Grid,
+    ticks: u32,
+    expected: String,
+}
+
+fn parse_case(text: &str) -> Result<Case, String> {
+    let (head, expected) = text.split_once(&format!("\n{}\n", SEPARATOR)).ok_or("Missing --- line")?;
+    let mut ticks = None;
+    let mut wrap = false;
+    let mut grid_lines = Vec::new();
+    for line in head.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        match trimmed.split_once('=') {
+            Some((key, value)) if grid_lines.is_empty() => match key.trim() {
+                "ticks" => ticks = Some(value.trim().parse().map_err(|_| "Invalid ticks")?),
+                "wrap" => wrap = value.trim().parse().map_err(|_| "wrap must be true or false")?,
+                key => return Err(format!("Unknown key: {}", key)),
+            },
+            _ => grid_lines.push(trimmed),
+        }
+    }
+
+    let mut grid = asciigrid::decode(&grid_lines.join("\n"))?;
+    grid.wrap = wrap;
+    Ok(Case {
+        head: format!("{}\n{}\n", head, SEPARATOR),
+        grid,
+        ticks: ticks.ok_or("Missing ticks")?,
+        expected: expected.to_string(),
+    })
+}
+
+#[test]
+fn golden() {
+    let bless = env::var_os("BLESS").is_some();
+    let mut paths: Vec<_> = fs::read_dir(CASES_DIR)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "No cases in {}", CASES_DIR);
+
+    let mut failures = Vec::new();
+    for path in &paths {
+        let name = path.file_stem().unwrap().to_string_lossy();
+        let text = fs::read_to_string(path).unwrap();
+        let mut case = match parse_case(&text) {
+            Ok(case) => case,
+            Err(err) => {
+                failures.push(format!("{}: {}", name, err));
+                continue;
+            }
+        };
+
+        for _ in 0..case.ticks {
+            case.grid.step();
+        }
+        let actual = asciigrid::encode(&case.grid);
+        if actual.trim_end() == case.expected.trim_end() {
+            continue;
+        }
+        if bless {
+            fs::write(Path::new(path), format!("{}{}", case.head, actual)).unwrap();
+        } else {
+            failures.push(format!("{}\nexpected:\n{}\nactual:\n{}", name, case.expected.trim_end(), actual.trim_end()));
+        }
+    }
+
+    assert!(failures.is_empty(), "{} of {} cases failed:\n\n{}", failures.len(), paths.len(), failures.join("\n\n"));
+}